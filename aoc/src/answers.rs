@@ -0,0 +1,86 @@
+use anyhow::{Context as _, Result};
+use common::Part;
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+/// The known-good answers for a day, one `part<TAB>answer` line each, kept
+/// in `day-NN/answers.txt`.
+#[derive(Debug)]
+pub struct Answers {
+    path: PathBuf,
+    answers: BTreeMap<Part, String>,
+}
+
+impl Answers {
+    pub fn load(day_dir: &Path) -> Result<Self> {
+        let path = day_dir.join("answers.txt");
+        let contents = match fs::read_to_string(&path) {
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            contents => contents.with_context(|| format!("reading {}", path.display()))?,
+        };
+        let answers = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                parse_line(line).with_context(|| format!("{}:{}", path.display(), i + 1))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Answers { path, answers })
+    }
+
+    pub fn get(&self, part: Part) -> Option<&str> {
+        self.answers.get(&part).map(String::as_str)
+    }
+
+    pub fn set(&mut self, part: Part, answer: &str) {
+        self.answers.insert(part, answer.to_string());
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let mut contents = String::new();
+        for (part, answer) in &self.answers {
+            writeln!(contents, "{part}\t{answer}")?;
+        }
+        fs::write(&self.path, contents).with_context(|| format!("writing {}", self.path.display()))
+    }
+}
+
+fn parse_line(line: &str) -> Result<(Part, String)> {
+    let (part, answer) = line
+        .split_once('\t')
+        .context("expected `part<TAB>answer`")?;
+    Ok((Part::try_from(part.parse::<u8>()?)?, answer.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+
+        let mut answers = Answers::load(dir)?;
+        assert_eq!(None, answers.get(Part::One));
+        answers.set(Part::Two, "5905");
+        answers.set(Part::One, "6440");
+        answers.save()?;
+
+        let answers = Answers::load(dir)?;
+        assert_eq!(Some("6440"), answers.get(Part::One));
+        assert_eq!(Some("5905"), answers.get(Part::Two));
+        assert_eq!(
+            "1\t6440\n2\t5905\n",
+            fs::read_to_string(dir.join("answers.txt"))?
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+use anyhow::{Context as _, Result};
+use common::Part;
+use reqwest::{blocking, header::COOKIE};
+use std::{fmt, str::FromStr, time::Duration};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Advent of Code asks automated tools to say who they are.
+pub const USER_AGENT: &str = concat!(
+    "aoc-runner/",
+    env!("CARGO_PKG_VERSION"),
+    " (personal advent of code workspace, rust)"
+);
+
+/// What the site made of a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// wrong, without a hint which way
+    Wrong,
+    /// not checked at all, try again after `wait`
+    RateLimited {
+        wait: Duration,
+    },
+    AlreadySolved,
+}
+
+impl Outcome {
+    /// Read the outcome out of the page the site answers a submission with.
+    pub fn classify(page: &str) -> Result<Self> {
+        let outcome = if page.contains("That's the right answer") {
+            Outcome::Correct
+        } else if page.contains("You gave an answer too recently") {
+            Outcome::RateLimited {
+                wait: parse_wait(page).unwrap_or(Duration::from_secs(60)),
+            }
+        } else if page.contains("Did you already complete it") {
+            Outcome::AlreadySolved
+        } else if page.contains("That's not the right answer") {
+            if page.contains("your answer is too high") {
+                Outcome::TooHigh
+            } else if page.contains("your answer is too low") {
+                Outcome::TooLow
+            } else {
+                Outcome::Wrong
+            }
+        } else {
+            anyhow::bail!("unrecognised response page:\n{}", page.trim());
+        };
+        Ok(outcome)
+    }
+}
+
+/// Parse the `You have 1m 5s left to wait.` part of a rate limit message.
+fn parse_wait(page: &str) -> Option<Duration> {
+    let start = page.find("You have ")? + "You have ".len();
+    let end = start + page[start..].find(" left to wait")?;
+    let mut secs = 0;
+    for amount in page[start..end].split_whitespace() {
+        let unit = amount.chars().last()?;
+        let n: u64 = amount[..amount.len() - 1].parse().ok()?;
+        secs += match unit {
+            'h' => n * 60 * 60,
+            'm' => n * 60,
+            's' => n,
+            _ => return None,
+        };
+    }
+    Some(Duration::from_secs(secs))
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Correct => f.write_str("correct"),
+            Outcome::TooHigh => f.write_str("too-high"),
+            Outcome::TooLow => f.write_str("too-low"),
+            Outcome::Wrong => f.write_str("wrong"),
+            Outcome::RateLimited { wait } => write!(f, "rate-limited, wait {}s", wait.as_secs()),
+            Outcome::AlreadySolved => f.write_str("already-solved"),
+        }
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = anyhow::Error;
+
+    /// Parse the outcomes worth recording, which is all but rate limits.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "correct" => Outcome::Correct,
+            "too-high" => Outcome::TooHigh,
+            "too-low" => Outcome::TooLow,
+            "wrong" => Outcome::Wrong,
+            "already-solved" => Outcome::AlreadySolved,
+            s => anyhow::bail!("unknown outcome `{s}`"),
+        })
+    }
+}
+
+pub struct Client {
+    base_url: String,
+    session: String,
+    year: u32,
+    http: blocking::Client,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str, year: u32) -> Self {
+        Client {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            year,
+            http: blocking::Client::builder()
+                .user_agent(USER_AGENT)
+                .build()
+                .expect("a client with only a user agent set should build"),
+        }
+    }
+
+    /// Configure from `SESSION` and `AOC_BASE_URL`, reading `.env` if present.
+    pub fn from_env(year: u32) -> Result<Self> {
+        dotenv::dotenv().ok();
+        let session = std::env::var("SESSION").context("SESSION must be set, see the justfile")?;
+        let base_url =
+            std::env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+        Ok(Client::new(&base_url, &session, year))
+    }
+
+    pub fn submit(&self, day: u8, part: Part, answer: &str) -> Result<Outcome> {
+        let url = format!("{}/{}/day/{day}/answer", self.base_url, self.year);
+        let response = self
+            .http
+            .post(&url)
+            .header(COOKIE, format!("session={}", self.session))
+            .form(&[("level", part.to_string()), ("answer", answer.to_string())])
+            .send()
+            .with_context(|| format!("posting to {url}"))?;
+        let status = response.status();
+        let page = response.text().context("reading the response")?;
+        if !status.is_success() {
+            anyhow::bail!("submitting failed with {status}: {}", page.trim());
+        }
+        Outcome::classify(&page)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mock_aoc::{bundled_fixtures, MockServer};
+
+    #[test]
+    fn test_classify() -> anyhow::Result<()> {
+        let page = |message: &str| format!("<main><article><p>{message}</p></article></main>");
+        assert_eq!(
+            Outcome::Correct,
+            Outcome::classify(&page(
+                "That's the right answer!  You are one gold star closer."
+            ))?
+        );
+        assert_eq!(
+            Outcome::TooLow,
+            Outcome::classify(&page(
+                "That's not the right answer; your answer is too low."
+            ))?
+        );
+        assert_eq!(
+            Outcome::Wrong,
+            Outcome::classify(&page("That's not the right answer.  If you're stuck, ..."))?
+        );
+        assert_eq!(
+            Outcome::RateLimited {
+                wait: Duration::from_secs(65)
+            },
+            Outcome::classify(&page(
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait."
+            ))?
+        );
+        assert_eq!(
+            Outcome::AlreadySolved,
+            Outcome::classify(&page(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            ))?
+        );
+        assert!(Outcome::classify("<html>maintenance</html>").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_submit() -> anyhow::Result<()> {
+        let addr = MockServer::new(bundled_fixtures(), Duration::ZERO).spawn()?;
+        let client = Client::new(&format!("http://{addr}/"), "abc", 2023);
+        assert_eq!(Outcome::TooHigh, client.submit(1, Part::One, "143")?);
+        assert_eq!(Outcome::Correct, client.submit(1, Part::One, "142")?);
+        assert_eq!(Outcome::AlreadySolved, client.submit(1, Part::One, "142")?);
+        assert!(client.submit(25, Part::One, "1").is_err());
+
+        Ok(())
+    }
+}
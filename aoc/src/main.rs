@@ -0,0 +1,280 @@
+use anyhow::{Context as _, Result};
+use clap::{Parser, Subcommand};
+use client::{Client, Outcome};
+use common::{
+    input::{self, read_file, read_stdin},
+    Part,
+};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+mod answers;
+mod client;
+mod registry;
+mod scaffold;
+mod submissions;
+mod verify;
+
+use answers::Answers;
+use submissions::Submissions;
+
+#[derive(Parser, Debug)]
+#[clap(version)]
+struct Args {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// run one day, or every registered day
+    Run {
+        /// the day to run, e.g. `5`, or `all`
+        day: Days,
+        /// the part to run, both parts when omitted
+        part: Option<u8>,
+        /// read the puzzle input from this path instead of the day's
+        /// `input.txt`, or its `inputN.txt` override. use `-` to read from
+        /// stdin
+        #[clap(short, long)]
+        input: Option<PathBuf>,
+        /// the workspace root containing the `day-NN` directories
+        #[clap(long, default_value = ".")]
+        root: PathBuf,
+    },
+    /// solve one part and submit the answer. needs `SESSION` set, and
+    /// `AOC_BASE_URL` to submit anywhere but adventofcode.com
+    Submit {
+        /// the day to submit, e.g. `5`
+        day: u8,
+        /// the part to submit
+        part: u8,
+        /// read the puzzle input from this path instead of the day's input
+        /// file. use `-` to read from stdin
+        #[clap(short, long)]
+        input: Option<PathBuf>,
+        /// the workspace root containing the `day-NN` directories
+        #[clap(long, default_value = ".")]
+        root: PathBuf,
+        /// the aoc year being solved
+        #[clap(long, env = "AOC_YEAR", default_value_t = 2023)]
+        year: u32,
+    },
+    /// check solutions against the answers recorded in each day's
+    /// `answers.txt`, failing when any answer changed
+    Verify {
+        /// the day to verify, e.g. `5`, or `all`
+        #[clap(default_value = "all")]
+        day: Days,
+        /// record answers for parts that don't have one yet
+        #[clap(long)]
+        record: bool,
+        /// the workspace root containing the `day-NN` directories
+        #[clap(long, default_value = ".")]
+        root: PathBuf,
+    },
+    /// create a day's crate from `template/`, register it with this runner
+    /// and fetch its input when `SESSION` is set
+    New {
+        /// the day to create, e.g. `5` or `day-05`
+        #[clap(value_parser = scaffold::parse_day)]
+        day: u8,
+        /// don't fetch the input, examples and description
+        #[clap(long)]
+        no_fetch: bool,
+        /// the workspace root containing the `day-NN` directories
+        #[clap(long, default_value = ".")]
+        root: PathBuf,
+        /// the aoc year being solved
+        #[clap(long, env = "AOC_YEAR", default_value_t = 2023)]
+        year: u32,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Days {
+    All,
+    One(u8),
+}
+
+impl FromStr for Days {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "all" {
+            return Ok(Days::All);
+        }
+        s.parse()
+            .map(Days::One)
+            .map_err(|_| format!("day `{s}` must be a number or `all`"))
+    }
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    match args.command {
+        Command::Run {
+            day,
+            part,
+            input,
+            root,
+        } => run(day, part, input, &root),
+        Command::Submit {
+            day,
+            part,
+            input,
+            root,
+            year,
+        } => submit(day, Part::try_from(part)?, input, &root, year),
+        Command::Verify { day, record, root } => verify(day, record, &root),
+        Command::New {
+            day,
+            no_fetch,
+            root,
+            year,
+        } => new(day, !no_fetch, &root, year),
+    }
+}
+
+fn run(days: Days, part: Option<u8>, input: Option<PathBuf>, root: &Path) -> Result<()> {
+    let parts = match part {
+        Some(part) => vec![Part::try_from(part)?],
+        None => vec![Part::One, Part::Two],
+    };
+
+    let days: Vec<u8> = match days {
+        Days::All => {
+            if input.is_some() {
+                anyhow::bail!("--input can only be used when running a single day");
+            }
+            registry::days().collect()
+        }
+        Days::One(day) => vec![day],
+    };
+
+    // stdin can only be read once, so share it between both parts
+    let stdin = match &input {
+        Some(path) if path.as_os_str() == "-" => Some(read_stdin()?),
+        _ => None,
+    };
+    let mut failed = 0;
+    for &day in &days {
+        for &part in &parts {
+            let solution = registry::find(day, part)
+                .with_context(|| format!("day {day} part {part} is not registered"))?;
+
+            let contents = match (&stdin, &input) {
+                (Some(contents), _) => Ok(contents.clone()),
+                (None, Some(path)) => read_file(path),
+                (None, None) => read_file(&input_path(root, day, part)),
+            };
+
+            match contents.and_then(|contents| solution.run(&contents)) {
+                Ok(report) => {
+                    println!(
+                        "day {day:02} part {part} ({}): {} (parse {:?}, solve {:?})",
+                        solution.title(),
+                        report.answer,
+                        report.parse,
+                        report.solve,
+                    );
+                }
+                Err(e) => {
+                    failed += 1;
+                    println!("day {day:02} part {part}: error: {e:#}");
+                }
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{failed} part(s) failed");
+    }
+    Ok(())
+}
+
+fn submit(day: u8, part: Part, input: Option<PathBuf>, root: &Path, year: u32) -> Result<()> {
+    let solution = registry::find(day, part)
+        .with_context(|| format!("day {day} part {part} is not registered"))?;
+    let contents = match input {
+        Some(path) if path.as_os_str() == "-" => read_stdin()?,
+        Some(path) => read_file(&path)?,
+        None => read_file(&input_path(root, day, part))?,
+    };
+    let answer = solution.run(&contents)?.answer;
+
+    let mut submissions = Submissions::load(&day_dir(root, day))?;
+    submissions.check(part, &answer).context("not submitting")?;
+
+    let client = Client::from_env(year)?;
+    println!("submitting `{answer}` for day {day:02} part {part}");
+    let outcome = client.submit(day, part, &answer)?;
+    submissions.record(part, &answer, outcome)?;
+    if outcome != Outcome::Correct {
+        anyhow::bail!("`{answer}` wasn't accepted: {outcome}");
+    }
+    println!("{outcome}");
+
+    let mut answers = Answers::load(&day_dir(root, day))?;
+    answers.set(part, &answer);
+    answers.save()?;
+    Ok(())
+}
+
+fn verify(days: Days, record: bool, root: &Path) -> Result<()> {
+    let days: Vec<u8> = match days {
+        Days::All => registry::days().collect(),
+        Days::One(day) => vec![day],
+    };
+    let mut failed = 0;
+    for day in days {
+        for (part, verdict) in verify::verify_day(root, day, record)? {
+            if verdict.is_failure() {
+                failed += 1;
+            }
+            println!("day {day:02} part {part}: {verdict}");
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{failed} part(s) no longer match their recorded answers");
+    }
+    Ok(())
+}
+
+fn new(day: u8, fetch: bool, root: &Path, year: u32) -> Result<()> {
+    let dir = scaffold::new_day(root, day)?;
+    println!("created {} and registered it in aoc", dir.display());
+    if !fetch {
+        return Ok(());
+    }
+
+    // the day is usable without its input, so only warn when fetching fails
+    let name = format!("day-{day:02}");
+    dotenv::dotenv().ok();
+    if std::env::var_os("SESSION").is_none() {
+        println!("SESSION isn't set, fetch the input later with `just get-input {name}`");
+        return Ok(());
+    }
+    let status = std::process::Command::new(root.join("scripts/get-aoc-input.rs"))
+        .args(["--day", &name, "--year", &year.to_string()])
+        .arg("--current-working-directory")
+        .arg(root)
+        .status();
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => println!("fetching the input failed with {status}"),
+        Err(e) => println!("couldn't run the input fetcher: {e}"),
+    }
+    Ok(())
+}
+
+fn day_dir(root: &Path, day: u8) -> PathBuf {
+    root.join(format!("day-{day:02}"))
+}
+
+fn input_path(root: &Path, day: u8, part: Part) -> PathBuf {
+    input::path(day_dir(root, day), part)
+}
@@ -0,0 +1,55 @@
+use common::{DynSolution, Part};
+
+pub const SOLUTIONS: &[&dyn DynSolution] = &[
+    &day_01::part1::Part1,
+    &day_01::part2::Part2,
+    &day_02::part1::Part1,
+    &day_02::part2::Part2,
+    &day_03::part1::Part1,
+    &day_03::part2::Part2,
+    &day_04::part1::Part1,
+    &day_04::part2::Part2,
+    &day_05::part1::Part1,
+    &day_05::part2::Part2,
+    &day_06::part1::Part1,
+    &day_06::part2::Part2,
+    &day_07::part1::Part1,
+    &day_07::part2::Part2,
+    &day_08::part1::Part1,
+    &day_08::part2::Part2,
+    &day_09::part1::Part1,
+    &day_09::part2::Part2,
+];
+
+/// Every registered day, in ascending order.
+pub fn days() -> impl Iterator<Item = u8> {
+    let mut days: Vec<u8> = SOLUTIONS.iter().map(|s| s.day()).collect();
+    days.sort();
+    days.dedup();
+    days.into_iter()
+}
+
+pub fn find(day: u8, part: Part) -> Option<&'static dyn DynSolution> {
+    SOLUTIONS
+        .iter()
+        .copied()
+        .find(|s| s.day() == day && s.part() == part)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_unique() {
+        for day in days() {
+            for part in [Part::One, Part::Two] {
+                let count = SOLUTIONS
+                    .iter()
+                    .filter(|s| s.day() == day && s.part() == part)
+                    .count();
+                assert_eq!(1, count, "day {day} part {part}");
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use anyhow::{Context as _, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The files of a new day's crate, from `template/`. `{{day}}` is replaced
+/// by the day number, `{{package}}` by the package name and `{{crate}}` by
+/// the crate name.
+const TEMPLATE: &[(&str, &str)] = &[
+    ("Cargo.toml", include_str!("../../template/Cargo.toml")),
+    (".gitignore", include_str!("../../template/.gitignore")),
+    ("src/lib.rs", include_str!("../../template/src/lib.rs")),
+    ("src/part1.rs", include_str!("../../template/src/part1.rs")),
+    ("src/part2.rs", include_str!("../../template/src/part2.rs")),
+    (
+        "src/bin/part1.rs",
+        include_str!("../../template/src/bin/part1.rs"),
+    ),
+    (
+        "src/bin/part2.rs",
+        include_str!("../../template/src/bin/part2.rs"),
+    ),
+];
+
+/// Parse a day given as `5`, `05` or `day-05`.
+pub fn parse_day(s: &str) -> Result<u8> {
+    let number = s.strip_prefix("day-").unwrap_or(s);
+    match number.parse::<u8>() {
+        Ok(day @ 1..=25) => Ok(day),
+        _ => anyhow::bail!("day `{s}` must be a number from 1 to 25, e.g. `5` or `day-05`"),
+    }
+}
+
+fn render(template: &str, day: u8) -> String {
+    template
+        .replace("{{day}}", &day.to_string())
+        .replace("{{package}}", &format!("day-{day:02}"))
+        .replace("{{crate}}", &format!("day_{day:02}"))
+}
+
+/// Create `day-NN` under `root` from the template and register it with the
+/// runner, returning the new crate's directory.
+pub fn new_day(root: &Path, day: u8) -> Result<PathBuf> {
+    let dir = root.join(format!("day-{day:02}"));
+    if dir.exists() {
+        anyhow::bail!("{} already exists", dir.display());
+    }
+    for (path, template) in TEMPLATE {
+        let path = dir.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+        }
+        fs::write(&path, render(template, day))
+            .with_context(|| format!("writing {}", path.display()))?;
+    }
+
+    insert_sorted(
+        &root.join("aoc/Cargo.toml"),
+        &[format!("day-{day:02} = {{ path = \"../day-{day:02}\" }}")],
+        |line| line.starts_with("day-"),
+    )?;
+    insert_sorted(
+        &root.join("aoc/src/registry.rs"),
+        &[
+            format!("    &day_{day:02}::part1::Part1,"),
+            format!("    &day_{day:02}::part2::Part2,"),
+        ],
+        |line| line.trim_start().starts_with("&day_"),
+    )?;
+    Ok(dir)
+}
+
+/// Add `lines` to the sorted block of lines matching `is_entry` in the file
+/// at `path`, e.g. the day dependencies in a manifest.
+fn insert_sorted(path: &Path, lines: &[String], is_entry: impl Fn(&str) -> bool) -> Result<()> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let mut out: Vec<String> = contents.lines().map(str::to_string).collect();
+    let start = out
+        .iter()
+        .position(|line| is_entry(line))
+        .with_context(|| format!("no day entries to add to in {}", path.display()))?;
+    let end = start
+        + out[start..]
+            .iter()
+            .take_while(|line| is_entry(line))
+            .count();
+
+    let mut entries: Vec<String> = out.drain(start..end).chain(lines.iter().cloned()).collect();
+    entries.sort();
+    entries.dedup();
+    out.splice(start..start, entries);
+
+    let mut contents = out.join("\n");
+    contents.push('\n');
+    fs::write(path, contents).with_context(|| format!("writing {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_day() -> anyhow::Result<()> {
+        assert_eq!(5, parse_day("5")?);
+        assert_eq!(5, parse_day("05")?);
+        assert_eq!(12, parse_day("day-12")?);
+        assert!(parse_day("0").is_err());
+        assert!(parse_day("26").is_err());
+        assert!(parse_day("day-x").is_err());
+        assert!(parse_day("day5").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_day() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let root = tmp.path();
+        fs::create_dir_all(root.join("aoc/src"))?;
+        fs::write(
+            root.join("aoc/Cargo.toml"),
+            "[dependencies]\nanyhow.workspace = true\nday-01 = { path = \"../day-01\" }\nday-03 = { path = \"../day-03\" }\n\n[dev-dependencies]\n",
+        )?;
+        fs::write(
+            root.join("aoc/src/registry.rs"),
+            "pub const SOLUTIONS: &[&dyn DynSolution] = &[\n    &day_01::part1::Part1,\n    &day_01::part2::Part2,\n];\n",
+        )?;
+
+        let dir = new_day(root, 2)?;
+        assert_eq!(root.join("day-02"), dir);
+        for (path, _) in TEMPLATE {
+            let contents = fs::read_to_string(dir.join(path))?;
+            assert!(!contents.contains("{{"), "{path} has placeholders left");
+        }
+        assert!(fs::read_to_string(dir.join("Cargo.toml"))?.contains("name = \"day-02\""));
+        assert_eq!(
+            include_str!("../../template/.gitignore"),
+            fs::read_to_string(dir.join(".gitignore"))?
+        );
+        let part1 = fs::read_to_string(dir.join("src/part1.rs"))?;
+        assert!(part1.contains("const DAY: u8 = 2;"));
+        assert!(!part1.contains("todo!"), "a stub part must fail, not panic");
+        assert!(fs::read_to_string(dir.join("src/bin/part2.rs"))?.contains("use day_02::part2"));
+
+        assert_eq!(
+            "[dependencies]\nanyhow.workspace = true\nday-01 = { path = \"../day-01\" }\nday-02 = { path = \"../day-02\" }\nday-03 = { path = \"../day-03\" }\n\n[dev-dependencies]\n",
+            fs::read_to_string(root.join("aoc/Cargo.toml"))?
+        );
+        assert_eq!(
+            "pub const SOLUTIONS: &[&dyn DynSolution] = &[\n    &day_01::part1::Part1,\n    &day_01::part2::Part2,\n    &day_02::part1::Part1,\n    &day_02::part2::Part2,\n];\n",
+            fs::read_to_string(root.join("aoc/src/registry.rs"))?
+        );
+
+        assert!(new_day(root, 2).is_err());
+
+        Ok(())
+    }
+}
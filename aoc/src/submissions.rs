@@ -0,0 +1,154 @@
+use crate::client::Outcome;
+use anyhow::{Context as _, Result};
+use common::Part;
+use std::{
+    fs,
+    io::{ErrorKind, Write as _},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub part: Part,
+    pub answer: String,
+    pub outcome: Outcome,
+}
+
+/// Every answer submitted for a day, one `part<TAB>outcome<TAB>answer` line
+/// each, kept in `day-NN/submissions.txt`.
+#[derive(Debug)]
+pub struct Submissions {
+    path: PathBuf,
+    submissions: Vec<Submission>,
+}
+
+impl Submissions {
+    pub fn path(day_dir: &Path) -> PathBuf {
+        day_dir.join("submissions.txt")
+    }
+
+    pub fn load(day_dir: &Path) -> Result<Self> {
+        let path = Self::path(day_dir);
+        let contents = match fs::read_to_string(&path) {
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            contents => contents.with_context(|| format!("reading {}", path.display()))?,
+        };
+        let submissions = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                parse_line(line).with_context(|| format!("{}:{}", path.display(), i + 1))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Submissions { path, submissions })
+    }
+
+    /// Refuse answers that are already known to be wrong, either because
+    /// they were submitted before or because they are past a too high or
+    /// too low bound, and parts that are already solved, including ones the
+    /// site said were solved elsewhere.
+    pub fn check(&self, part: Part, answer: &str) -> Result<()> {
+        let number = answer.parse::<i128>().ok();
+        for s in self.submissions.iter().filter(|s| s.part == part) {
+            match s.outcome {
+                Outcome::Correct => {
+                    anyhow::bail!("part {part} is already solved with `{}`", s.answer)
+                }
+                Outcome::AlreadySolved => anyhow::bail!("part {part} was already solved"),
+                _ => {}
+            }
+            if s.answer == answer {
+                anyhow::bail!("`{answer}` was already submitted and was {}", s.outcome);
+            }
+            let Some((number, bound)) = number.zip(s.answer.parse::<i128>().ok()) else {
+                continue;
+            };
+            match s.outcome {
+                Outcome::TooHigh if number >= bound => {
+                    anyhow::bail!("`{answer}` can't be right, `{bound}` was already too high")
+                }
+                Outcome::TooLow if number <= bound => {
+                    anyhow::bail!("`{answer}` can't be right, `{bound}` was already too low")
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Append a submission to the log. Rate limited submissions weren't
+    /// checked, so they aren't recorded.
+    pub fn record(&mut self, part: Part, answer: &str, outcome: Outcome) -> Result<()> {
+        if let Outcome::RateLimited { .. } = outcome {
+            return Ok(());
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("opening {}", self.path.display()))?;
+        writeln!(file, "{part}\t{outcome}\t{answer}")
+            .with_context(|| format!("writing {}", self.path.display()))?;
+        self.submissions.push(Submission {
+            part,
+            answer: answer.to_string(),
+            outcome,
+        });
+        Ok(())
+    }
+}
+
+fn parse_line(line: &str) -> Result<Submission> {
+    let mut fields = line.splitn(3, '\t');
+    let (Some(part), Some(outcome), Some(answer)) = (fields.next(), fields.next(), fields.next())
+    else {
+        anyhow::bail!("expected `part<TAB>outcome<TAB>answer`");
+    };
+    Ok(Submission {
+        part: Part::try_from(part.parse::<u8>()?)?,
+        answer: answer.to_string(),
+        outcome: outcome.parse()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+
+        let mut submissions = Submissions::load(dir)?;
+        submissions.record(Part::One, "100", Outcome::TooLow)?;
+        submissions.record(Part::One, "200", Outcome::TooHigh)?;
+        submissions.record(Part::One, "abc", Outcome::Wrong)?;
+        submissions.record(
+            Part::One,
+            "150",
+            Outcome::RateLimited {
+                wait: Default::default(),
+            },
+        )?;
+
+        // reload to check the log round trips
+        let submissions = Submissions::load(dir)?;
+        assert_eq!(3, submissions.submissions.len());
+        assert!(submissions.check(Part::One, "abc").is_err());
+        assert!(submissions.check(Part::One, "100").is_err());
+        assert!(submissions.check(Part::One, "99").is_err());
+        assert!(submissions.check(Part::One, "250").is_err());
+        submissions.check(Part::One, "150")?;
+        submissions.check(Part::Two, "100")?;
+
+        let mut submissions = submissions;
+        submissions.record(Part::One, "150", Outcome::Correct)?;
+        assert!(submissions.check(Part::One, "151").is_err());
+        submissions.record(Part::Two, "7", Outcome::AlreadySolved)?;
+        assert!(submissions.check(Part::Two, "8").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{answers::Answers, day_dir, input_path, registry};
+use anyhow::Result;
+use common::{input::read_file, Part};
+use std::fmt;
+
+/// How one part's answer compares to the answers ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Match,
+    Diverged {
+        expected: String,
+        actual: String,
+    },
+    /// nothing is recorded yet, and recording wasn't asked for
+    Unrecorded {
+        answer: String,
+    },
+    /// nothing was recorded, so the answer was added to the ledger
+    Recorded {
+        answer: String,
+    },
+    NoInput,
+    Failed {
+        error: String,
+    },
+}
+
+impl Verdict {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Verdict::Diverged { .. } | Verdict::Failed { .. })
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Match => f.write_str("ok"),
+            Verdict::Diverged { expected, actual } => {
+                write!(f, "DIVERGED, expected `{expected}` but got `{actual}`")
+            }
+            Verdict::Unrecorded { answer } => {
+                write!(f, "unrecorded `{answer}`, pass --record to keep it")
+            }
+            Verdict::Recorded { answer } => write!(f, "recorded `{answer}`"),
+            Verdict::NoInput => f.write_str("skipped, no input"),
+            Verdict::Failed { error } => write!(f, "FAILED: {error}"),
+        }
+    }
+}
+
+/// Run every registered part of `day` against its input and compare the
+/// answers with the day's ledger, adding missing answers when `record`.
+pub fn verify_day(root: &std::path::Path, day: u8, record: bool) -> Result<Vec<(Part, Verdict)>> {
+    let mut answers = Answers::load(&day_dir(root, day))?;
+    let mut verdicts = vec![];
+    for part in [Part::One, Part::Two] {
+        let Some(solution) = registry::find(day, part) else {
+            continue;
+        };
+        let path = input_path(root, day, part);
+        if !path.exists() {
+            verdicts.push((part, Verdict::NoInput));
+            continue;
+        }
+        let answer = match read_file(&path).and_then(|input| solution.run(&input)) {
+            Ok(report) => report.answer,
+            Err(e) => {
+                verdicts.push((
+                    part,
+                    Verdict::Failed {
+                        error: format!("{e:#}"),
+                    },
+                ));
+                continue;
+            }
+        };
+        let verdict = match answers.get(part) {
+            Some(expected) if expected == answer => Verdict::Match,
+            Some(expected) => Verdict::Diverged {
+                expected: expected.to_string(),
+                actual: answer,
+            },
+            None if record => {
+                answers.set(part, &answer);
+                Verdict::Recorded { answer }
+            }
+            None => Verdict::Unrecorded { answer },
+        };
+        verdicts.push((part, verdict));
+    }
+    if verdicts
+        .iter()
+        .any(|(_, v)| matches!(v, Verdict::Recorded { .. }))
+    {
+        answers.save()?;
+    }
+    Ok(verdicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, path::Path};
+
+    /// The regression harness: every recorded answer in the workspace must
+    /// still come out of its solution. Puzzle inputs are gitignored, so this
+    /// only runs when asked for, and fails if nothing could be checked.
+    #[test]
+    #[ignore = "needs the puzzle inputs, fetch them and run with `--ignored`"]
+    fn test_workspace_answers() -> anyhow::Result<()> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let mut matched = 0;
+        for day in registry::days() {
+            for (part, verdict) in verify_day(&root, day, false)? {
+                assert!(!verdict.is_failure(), "day {day} part {part}: {verdict}");
+                if verdict == Verdict::Match {
+                    matched += 1;
+                }
+            }
+        }
+        assert!(matched > 0, "no inputs with recorded answers to verify");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_day() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let root = tmp.path();
+        let dir = day_dir(root, 1);
+        fs::create_dir_all(&dir)?;
+        fs::write(
+            dir.join("input.txt"),
+            "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n",
+        )?;
+        fs::write(dir.join("answers.txt"), "2\t1\n")?;
+
+        let verdicts = verify_day(root, 1, true)?;
+        assert_eq!(
+            Verdict::Recorded {
+                answer: "142".to_string()
+            },
+            verdicts[0].1
+        );
+        assert!(verdicts[1].1.is_failure());
+
+        let verdicts = verify_day(root, 1, false)?;
+        assert_eq!(Verdict::Match, verdicts[0].1);
+        assert_eq!(
+            None,
+            verify_day(root, 2, false)?
+                .into_iter()
+                .find(|(_, v)| *v != Verdict::NoInput)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+//! Puzzle examples kept as fixtures in a day's `examples/` directory, as
+//! `part{N}.txt` with the expected answer in `part{N}.answer`. The fetch
+//! script fills these in from the puzzle page.
+
+use crate::{input::read_file, Part};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub input: String,
+    pub answer: String,
+}
+
+/// Load the example for `part` from `dir`, or `None` when it hasn't been
+/// fetched yet.
+pub fn load(dir: &Path, part: Part) -> anyhow::Result<Option<Example>> {
+    let input_path = dir.join(format!("part{part}.txt"));
+    let answer_path = dir.join(format!("part{part}.answer"));
+    if !input_path.exists() || !answer_path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(Example {
+        input: read_file(&input_path)?,
+        answer: read_file(&answer_path)?.trim().to_string(),
+    }))
+}
+
+/// Generate a `test_example` that runs `$run` on the calling crate's
+/// `examples/part{$part}.txt` and checks the expected answer. Fails when the
+/// example hasn't been fetched by `aoc new` or `just get-input`, rather than
+/// passing without checking.
+#[macro_export]
+macro_rules! example_test {
+    ($run:path, $part:literal) => {
+        #[test]
+        fn test_example() -> anyhow::Result<()> {
+            let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+            let part = $crate::Part::try_from($part)?;
+            let Some(example) = $crate::examples::load(&dir, part)? else {
+                anyhow::bail!(
+                    "no example for part {part} in {}, fetch it with `just get-input`",
+                    dir.display()
+                );
+            };
+            assert_eq!(example.answer, $run(&example.input)?);
+            Ok(())
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        std::fs::write(dir.join("part1.txt"), "1\n2\n")?;
+        std::fs::write(dir.join("part1.answer"), "3\n")?;
+
+        let example = load(dir, Part::One)?.expect("example");
+        assert_eq!("1\n2\n", example.input);
+        assert_eq!("3", example.answer);
+        assert_eq!(None, load(dir, Part::Two)?);
+
+        Ok(())
+    }
+
+    fn sum(input: &str) -> anyhow::Result<String> {
+        Ok(input
+            .lines()
+            .map(str::parse::<u32>)
+            .sum::<Result<u32, _>>()?
+            .to_string())
+    }
+
+    // checks `sum` against this crate's examples/part1.txt
+    crate::example_test!(sum, 1);
+}
@@ -0,0 +1,491 @@
+//! A rectangular grid of cells, for the puzzles whose input is a picture.
+//! Positions are `(row, col)` with `(0, 0)` in the top left corner.
+
+use crate::ParseError;
+use std::{
+    fmt,
+    ops::{Index, IndexMut, Range},
+    str::FromStr,
+};
+
+/// A `(row, col)` position in a grid.
+pub type Pos = (usize, usize);
+
+/// Steps to the cells above, left, right and below.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Steps to every surrounding cell, row by row.
+pub const SURROUNDING: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// Something written across adjacent cells of one row, like a number.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Span<V> {
+    pub value: V,
+    pub row: usize,
+    pub cols: Range<usize>,
+}
+
+impl<V> Span<V> {
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let row = self.row;
+        self.cols.clone().map(move |col| (row, col))
+    }
+
+    /// Whether `pos` is next to the span, diagonals included. Positions on
+    /// the span itself don't count.
+    pub fn touches(&self, (row, col): Pos) -> bool {
+        let on = row == self.row && self.cols.contains(&col);
+        !on && row.abs_diff(self.row) <= 1 && col + 1 >= self.cols.start && col <= self.cols.end
+    }
+
+    /// Whether any cell of the span is next to any cell of `other`.
+    pub fn touches_span<U>(&self, other: &Span<U>) -> bool {
+        other.positions().any(|pos| self.touches(pos))
+    }
+}
+
+/// What [`Grid::tokens`] finds in a grid of characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Token {
+    /// a run of digits
+    Number(u64),
+    /// any other character that isn't blank
+    Symbol(char),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    /// row by row
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Parse a grid with one row per line, converting each character with
+    /// `cell`. Characters it returns `None` for are errors, reported as not
+    /// being `expected`, as are rows of different lengths.
+    pub fn parse_with(
+        input: &str,
+        expected: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for line in input.trim_end_matches(['\n', '\r']).lines() {
+            let width = *width.get_or_insert(line.chars().count());
+            for (i, (offset, c)) in line.char_indices().enumerate() {
+                if i == width {
+                    let expected = format!("the end of a row of {width} cells");
+                    return Err(ParseError::within(input, &line[offset..], expected));
+                }
+                let value = cell(c).ok_or_else(|| {
+                    ParseError::within(input, &line[offset..], expected.to_string())
+                })?;
+                cells.push(value);
+            }
+            if cells.len() < (height + 1) * width {
+                let expected = format!("a row of {width} cells");
+                return Err(ParseError::within(input, &line[line.len()..], expected));
+            }
+            height += 1;
+        }
+        Ok(Grid {
+            width: width.unwrap_or_default(),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (row, col): Pos) -> bool {
+        row < self.height && col < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.0 * self.width + pos.1])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.contains(pos)
+            .then(|| &mut self.cells[pos.0 * self.width + pos.1])
+    }
+
+    /// The position one `step` away from `pos`, if it's in the grid.
+    pub fn step(&self, (row, col): Pos, (d_row, d_col): (isize, isize)) -> Option<Pos> {
+        let pos = (
+            row.checked_add_signed(d_row)?,
+            col.checked_add_signed(d_col)?,
+        );
+        self.contains(pos).then_some(pos)
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The cells above, left, right and below `pos` that are in the grid.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = (Pos, &T)> {
+        self.around(pos, &ORTHOGONAL)
+    }
+
+    /// The cells surrounding `pos`, diagonals included, that are in the grid.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = (Pos, &T)> {
+        self.around(pos, &SURROUNDING)
+    }
+
+    fn around<'a>(
+        &'a self,
+        pos: Pos,
+        steps: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (Pos, &'a T)> {
+        steps
+            .iter()
+            .filter_map(move |&step| self.step(pos, step))
+            .map(|pos| (pos, &self[pos]))
+    }
+
+    /// The cells from `pos` onwards, taking `step` each time until leaving
+    /// the grid. Empty when `pos` isn't in the grid.
+    pub fn ray(&self, pos: Pos, step: (isize, isize)) -> impl Iterator<Item = (Pos, &T)> {
+        std::iter::successors(Some(pos).filter(|&pos| self.contains(pos)), move |&pos| {
+            self.step(pos, step)
+        })
+        .map(|pos| (pos, &self[pos]))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        (row < self.height).then(|| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    /// The cells of column `col` from top to bottom, empty if there's no
+    /// such column.
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.ray((0, col), (1, 0)).map(|(_, cell)| cell)
+    }
+
+    /// The cells from `pos` going down and to the right.
+    pub fn diagonal(&self, pos: Pos) -> impl Iterator<Item = &T> {
+        self.ray(pos, (1, 1)).map(|(_, cell)| cell)
+    }
+
+    /// The cells from `pos` going down and to the left.
+    pub fn anti_diagonal(&self, pos: Pos) -> impl Iterator<Item = &T> {
+        self.ray(pos, (1, -1)).map(|(_, cell)| cell)
+    }
+
+    /// The positions of every cell matching `predicate`, row by row.
+    pub fn find_all<'a>(
+        &'a self,
+        mut predicate: impl FnMut(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Pos> + 'a {
+        self.iter()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    /// The runs of adjacent cells in each row that all match `predicate`,
+    /// row by row.
+    pub fn runs(&self, mut predicate: impl FnMut(&T) -> bool) -> Vec<Span<&[T]>> {
+        let mut runs = vec![];
+        for (row, cells) in self.rows().enumerate() {
+            let mut start = None;
+            for col in 0..=cells.len() {
+                match (start, cells.get(col).is_some_and(&mut predicate)) {
+                    (None, true) => start = Some(col),
+                    (Some(from), false) => {
+                        runs.push(Span {
+                            value: &cells[from..col],
+                            row,
+                            cols: from..col,
+                        });
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        runs
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Swap rows and columns.
+    pub fn transpose(&self) -> Self {
+        self.rebuild(self.height, |(row, col)| (col, row))
+    }
+
+    /// Turn a quarter clockwise, so the left column becomes the top row.
+    pub fn rotate_clockwise(&self) -> Self {
+        let height = self.height;
+        self.rebuild(height, |(row, col)| (height - 1 - col, row))
+    }
+
+    /// Turn a quarter counterclockwise, so the right column becomes the top
+    /// row.
+    pub fn rotate_counterclockwise(&self) -> Self {
+        let width = self.width;
+        self.rebuild(self.height, |(row, col)| (col, width - 1 - row))
+    }
+
+    /// A grid `width` wide and as big as this one, taking each cell from the
+    /// position `source` gives for it.
+    fn rebuild(&self, width: usize, source: impl Fn(Pos) -> Pos) -> Self {
+        let height = self.cells.len().checked_div(width).unwrap_or_default();
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(|pos| self[source(pos)].clone())
+            .collect();
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+}
+
+impl Grid<char> {
+    /// Every number and every symbol, which is any character but a digit or
+    /// `blank`, row by row. Symbols are always one character wide.
+    pub fn tokens(&self, blank: char) -> Result<Vec<Span<Token>>, ParseError> {
+        let mut tokens = vec![];
+        for span in self.runs(|c| c.is_ascii_digit()) {
+            let value = span
+                .value
+                .iter()
+                .filter_map(|c| c.to_digit(10))
+                .try_fold(0u64, |n, digit| {
+                    n.checked_mul(10)?.checked_add(digit.into())
+                })
+                .ok_or_else(|| ParseError {
+                    line: span.row + 1,
+                    column: span.cols.start + 1,
+                    snippet: self.row(span.row).unwrap_or_default().iter().collect(),
+                    expected: "a number that fits in 64 bits".to_string(),
+                })?;
+            tokens.push(Span {
+                value: Token::Number(value),
+                row: span.row,
+                cols: span.cols,
+            });
+        }
+        for (row, col) in self.find_all(|&c| c != blank && !c.is_ascii_digit()) {
+            tokens.push(Span {
+                value: Token::Symbol(self[(row, col)]),
+                row,
+                cols: col..col + 1,
+            });
+        }
+        tokens.sort_by_key(|span| (span.row, span.cols.start));
+        Ok(tokens)
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::parse_with(s, "a character", Some)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the {}x{} grid", self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the {width}x{height} grid"))
+    }
+}
+
+/// One line per row, without a trailing newline.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> anyhow::Result<()> {
+        let grid: Grid<char> = "abc\ndef\n".parse()?;
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(Some(&'f'), grid.get((1, 2)));
+        assert_eq!(None, grid.get((2, 0)));
+        assert_eq!(None, grid.get((0, 3)));
+        assert_eq!("abc\ndef", grid.to_string());
+
+        let err = "abc\nde\n".parse::<Grid<char>>().unwrap_err();
+        assert_eq!((2, 3), (err.line, err.column));
+        let err = "abc\ndefg\n".parse::<Grid<char>>().unwrap_err();
+        assert_eq!((2, 4), (err.line, err.column));
+
+        let digits = Grid::parse_with("12\n34", "a digit", |c| c.to_digit(10))?;
+        assert_eq!(4, digits[(1, 1)]);
+        let err = Grid::parse_with("12\n3x", "a digit", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(
+            (2, 2, "a digit"),
+            (err.line, err.column, err.expected.as_str())
+        );
+
+        let empty: Grid<char> = "".parse()?;
+        assert_eq!((0, 0), (empty.width(), empty.height()));
+        assert_eq!("", empty.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_neighbours() -> anyhow::Result<()> {
+        let grid: Grid<char> = "abc\ndef\nghi".parse()?;
+        let around =
+            |cells: Vec<(Pos, &char)>| cells.into_iter().map(|(_, &c)| c).collect::<String>();
+        assert_eq!("bdfh", around(grid.neighbours4((1, 1)).collect()));
+        assert_eq!("abcdfghi", around(grid.neighbours8((1, 1)).collect()));
+        assert_eq!("bd", around(grid.neighbours4((0, 0)).collect()));
+        assert_eq!("efh", around(grid.neighbours8((2, 2)).collect()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_views() -> anyhow::Result<()> {
+        let grid: Grid<char> = "abc\ndef\nghi".parse()?;
+        assert_eq!(Some(&['d', 'e', 'f'][..]), grid.row(1));
+        assert_eq!(None, grid.row(3));
+        assert_eq!("beh", grid.column(1).collect::<String>());
+        assert_eq!("", grid.column(3).collect::<String>());
+        assert_eq!("aei", grid.diagonal((0, 0)).collect::<String>());
+        assert_eq!("ceg", grid.anti_diagonal((0, 2)).collect::<String>());
+        assert_eq!(
+            "ihg",
+            grid.ray((2, 2), (0, -1))
+                .map(|(_, c)| c)
+                .collect::<String>()
+        );
+        assert_eq!(
+            vec![(0, 0), (1, 1)],
+            grid.find_all(|c| "ae".contains(*c)).collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tokens() -> anyhow::Result<()> {
+        let grid: Grid<char> = "12.*\n#..7\n.345".parse()?;
+        let tokens = grid.tokens('.')?;
+        let spans: Vec<_> = tokens
+            .iter()
+            .map(|span| (span.value, span.row, span.cols.clone()))
+            .collect();
+        assert_eq!(
+            vec![
+                (Token::Number(12), 0, 0..2),
+                (Token::Symbol('*'), 0, 3..4),
+                (Token::Symbol('#'), 1, 0..1),
+                (Token::Number(7), 1, 3..4),
+                (Token::Number(345), 2, 1..4),
+            ],
+            spans
+        );
+
+        // 12 touches # below it, but not * two columns over
+        assert!(tokens[0].touches((1, 0)));
+        assert!(!tokens[0].touches((0, 3)));
+        assert!(!tokens[0].touches((0, 1)));
+        assert!(tokens[4].touches_span(&tokens[2]));
+        assert!(tokens[3].touches_span(&tokens[1]));
+
+        let err = "1.99999999999999999999"
+            .parse::<Grid<char>>()?
+            .tokens('.')
+            .unwrap_err();
+        assert_eq!((1, 3), (err.line, err.column));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_transform() -> anyhow::Result<()> {
+        let grid: Grid<char> = "abc\ndef".parse()?;
+        assert_eq!("ad\nbe\ncf", grid.transpose().to_string());
+        assert_eq!("da\neb\nfc", grid.rotate_clockwise().to_string());
+        assert_eq!("cf\nbe\nad", grid.rotate_counterclockwise().to_string());
+        assert_eq!(grid, grid.rotate_clockwise().rotate_counterclockwise());
+
+        let mut lit = grid.map(|&c| c == 'e');
+        lit[(0, 0)] = true;
+        assert_eq!(
+            vec![(0, 0), (1, 1)],
+            lit.find_all(|&on| on).collect::<Vec<_>>()
+        );
+        assert_eq!("..\n..", Grid::filled(2, 2, '.').to_string());
+
+        Ok(())
+    }
+}
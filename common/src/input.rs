@@ -0,0 +1,136 @@
+//! Loading puzzle input at runtime, so a part binary can be pointed at any
+//! input without a rebuild.
+
+use crate::Part;
+use anyhow::Context as _;
+use std::{
+    io::{ErrorKind, Read as _},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Source {
+    /// Pick the source from command line arguments, without the program
+    /// name: `--input <path>` (or `-i <path>`) reads that file, and a path
+    /// of `-` reads stdin. Anything else falls back to `default`.
+    pub fn from_args<I>(args: I, default: &Path) -> anyhow::Result<Self>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+        let mut source = Source::File(default.to_path_buf());
+        while let Some(arg) = args.next() {
+            let path = match arg.as_str() {
+                "--input" | "-i" => args
+                    .next()
+                    .with_context(|| format!("`{arg}` needs a path, or `-` for stdin"))?,
+                _ => match arg.strip_prefix("--input=") {
+                    Some(path) => path.to_string(),
+                    None => anyhow::bail!("unexpected argument `{arg}`, expected `--input <path>`"),
+                },
+            };
+            source = match path.as_str() {
+                "-" => Source::Stdin,
+                path => Source::File(path.into()),
+            };
+        }
+        Ok(source)
+    }
+
+    pub fn read(&self) -> anyhow::Result<String> {
+        match self {
+            Source::Stdin => read_stdin(),
+            Source::File(path) => read_file(path),
+        }
+    }
+}
+
+/// The input file for `part` in a day's directory: `input{part}.txt` when
+/// there is one, as an override for hand-crafted inputs, and otherwise the
+/// `input.txt` both parts share.
+pub fn path(dir: impl AsRef<Path>, part: Part) -> PathBuf {
+    let dir = dir.as_ref();
+    let part_path = dir.join(format!("input{part}.txt"));
+    if part_path.exists() {
+        return part_path;
+    }
+    dir.join("input.txt")
+}
+
+/// Read the input named on the command line, or the file at `default`.
+pub fn load(default: impl AsRef<Path>) -> anyhow::Result<String> {
+    Source::from_args(std::env::args().skip(1), default.as_ref())?.read()
+}
+
+pub fn read_file(path: &Path) -> anyhow::Result<String> {
+    match std::fs::read_to_string(path) {
+        Err(e) if e.kind() == ErrorKind::NotFound => Err(e).with_context(|| {
+            format!(
+                "no puzzle input at {}, fetch it with `just get-input` or pass `--input <path>`",
+                path.display()
+            )
+        }),
+        contents => contents.with_context(|| format!("reading {}", path.display())),
+    }
+}
+
+pub fn read_stdin() -> anyhow::Result<String> {
+    let mut contents = String::new();
+    std::io::stdin()
+        .read_to_string(&mut contents)
+        .context("reading stdin")?;
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(args: &[&str]) -> anyhow::Result<Source> {
+        Source::from_args(args.iter().map(|a| a.to_string()), Path::new("input.txt"))
+    }
+
+    #[test]
+    fn test_from_args() -> anyhow::Result<()> {
+        assert_eq!(Source::File("input.txt".into()), source(&[])?);
+        assert_eq!(
+            Source::File("other.txt".into()),
+            source(&["--input", "other.txt"])?
+        );
+        assert_eq!(
+            Source::File("other.txt".into()),
+            source(&["--input=other.txt"])?
+        );
+        assert_eq!(Source::Stdin, source(&["-i", "-"])?);
+        assert!(source(&["--input"]).is_err());
+        assert!(source(&["other.txt"]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_path() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        std::fs::write(dir.join("input.txt"), "shared")?;
+        std::fs::write(dir.join("input2.txt"), "override")?;
+
+        assert_eq!(dir.join("input.txt"), path(dir, Part::One));
+        assert_eq!(dir.join("input2.txt"), path(dir, Part::Two));
+        assert_eq!("override", read_file(&path(dir, Part::Two))?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let err = read_file(Path::new("does/not/exist.txt")).expect_err("missing");
+        assert!(err.to_string().contains("does/not/exist.txt"), "{err}");
+        assert!(err.to_string().contains("--input"), "{err}");
+    }
+}
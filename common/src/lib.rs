@@ -0,0 +1,11 @@
+pub mod examples;
+pub mod grid;
+pub mod input;
+pub mod math;
+pub mod parse;
+pub mod puzzle;
+pub mod solution;
+
+pub use grid::Grid;
+pub use parse::ParseError;
+pub use solution::{DynSolution, Part, Report, Solution};
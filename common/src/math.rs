@@ -0,0 +1,223 @@
+//! Number theory helpers shared between days.
+//!
+//! The fixed width functions never overflow silently: they either cannot
+//! overflow, or report it as [`MathError::Overflow`]. Reach for the `_big`
+//! variants when the answer might not fit at all.
+
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, PrimInt, Unsigned, Zero};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathError {
+    /// the result does not fit in the integer type
+    Overflow,
+    /// a modulus was zero or negative
+    InvalidModulus,
+    /// the number has no inverse for the modulus
+    NoInverse,
+    /// the congruences can never hold at the same time
+    NoSolution,
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MathError::Overflow => "arithmetic overflow",
+            MathError::InvalidModulus => "modulus must be positive",
+            MathError::NoInverse => "no modular inverse exists",
+            MathError::NoSolution => "congruences have no common solution",
+        })
+    }
+}
+
+impl std::error::Error for MathError {}
+
+pub fn gcd<T: PrimInt + Unsigned>(mut a: T, mut b: T) -> T {
+    while !b.is_zero() {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple of two numbers, or `None` on overflow. Divides
+/// before multiplying so intermediate values never exceed the result.
+pub fn checked_lcm<T: PrimInt + Unsigned>(a: T, b: T) -> Option<T> {
+    if a.is_zero() || b.is_zero() {
+        return Some(T::zero());
+    }
+    (a / gcd(a, b)).checked_mul(&b)
+}
+
+/// Least common multiple of every number, `1` when there are none.
+pub fn lcm<T, I>(nums: I) -> Result<T, MathError>
+where
+    T: PrimInt + Unsigned,
+    I: IntoIterator<Item = T>,
+{
+    nums.into_iter().try_fold(T::one(), |acc, n| {
+        checked_lcm(acc, n).ok_or(MathError::Overflow)
+    })
+}
+
+/// Arbitrary precision version of [`lcm`].
+pub fn lcm_big<I>(nums: I) -> BigUint
+where
+    I: IntoIterator,
+    I::Item: Into<BigUint>,
+{
+    nums.into_iter()
+        .fold(BigUint::one(), |acc, n| acc.lcm(&n.into()))
+}
+
+/// Returns `(g, x, y)` such that `a*x + b*y = g`, where `g` is the
+/// non-negative greatest common divisor of `a` and `b`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        return (-old_r, -old_x, -old_y);
+    }
+    (old_r, old_x, old_y)
+}
+
+/// The `x` in `0..m` with `a*x = 1 (mod m)`.
+pub fn mod_inverse(a: i128, m: i128) -> Result<i128, MathError> {
+    if m <= 0 {
+        return Err(MathError::InvalidModulus);
+    }
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    if g != 1 {
+        return Err(MathError::NoInverse);
+    }
+    Ok(x.rem_euclid(m))
+}
+
+/// Solve a system of congruences `x = residue (mod modulus)` given as
+/// `(residue, modulus)` pairs, returning `(x, lcm of the moduli)` with `x`
+/// the smallest non-negative solution. The moduli don't need to be
+/// coprime. An empty system is `(0, 1)`.
+pub fn crt<I>(congruences: I) -> Result<(i128, i128), MathError>
+where
+    I: IntoIterator<Item = (i128, i128)>,
+{
+    congruences.into_iter().try_fold((0, 1), |(a, m), (b, n)| {
+        if n <= 0 {
+            return Err(MathError::InvalidModulus);
+        }
+        let b = b.rem_euclid(n);
+        let (g, p, _) = extended_gcd(m, n);
+        if (b - a) % g != 0 {
+            return Err(MathError::NoSolution);
+        }
+        let n_g = n / g;
+        let lcm = (m / g).checked_mul(n).ok_or(MathError::Overflow)?;
+        // reduce before multiplying, so only k * m can get near lcm
+        let k = ((b - a) / g % n_g)
+            .checked_mul(p % n_g)
+            .ok_or(MathError::Overflow)?
+            .rem_euclid(n_g);
+        Ok(((a + m * k).rem_euclid(lcm), lcm))
+    })
+}
+
+/// Arbitrary precision version of [`crt`].
+pub fn crt_big<I>(congruences: I) -> Result<(BigInt, BigInt), MathError>
+where
+    I: IntoIterator<Item = (BigInt, BigInt)>,
+{
+    congruences
+        .into_iter()
+        .try_fold((BigInt::zero(), BigInt::one()), |(a, m), (b, n)| {
+            if n <= BigInt::zero() {
+                return Err(MathError::InvalidModulus);
+            }
+            let b = b.mod_floor(&n);
+            let egcd = m.extended_gcd(&n);
+            let g = egcd.gcd;
+            let diff = &b - &a;
+            if !diff.is_multiple_of(&g) {
+                return Err(MathError::NoSolution);
+            }
+            let n_g = &n / &g;
+            let lcm = &m / &g * &n;
+            let k = (diff / &g * egcd.x).mod_floor(&n_g);
+            Ok(((a + m * k).mod_floor(&lcm), lcm))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(6u64, gcd(48, 18));
+        assert_eq!(7u64, gcd(0, 7));
+        assert_eq!(Ok(60u64), lcm([4, 5, 6]));
+        assert_eq!(Ok(1u32), lcm([]));
+        assert_eq!(Some(0u8), checked_lcm(0, 5));
+    }
+
+    #[test]
+    fn test_lcm_overflow() {
+        // each step divides first, so this fits even though 2^40 * 2^40 doesn't
+        assert_eq!(Ok(1u64 << 40), lcm([1u64 << 40, 1 << 40]));
+        assert_eq!(Err(MathError::Overflow), lcm([u64::MAX, u64::MAX - 1]));
+
+        let big = lcm_big([u64::MAX, u64::MAX - 1]);
+        assert_eq!(BigUint::from(u64::MAX) * BigUint::from(u64::MAX - 1), big);
+        assert_eq!(
+            Ok(u64::MAX as u128 * (u64::MAX - 1) as u128),
+            lcm([u64::MAX as u128, (u64::MAX - 1) as u128])
+        );
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [(240, 46), (-240, 46), (17, 0), (0, 0), (46, -240)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, a * x + b * y, "{a} {b}");
+            assert!(g >= 0);
+        }
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(Ok(4), mod_inverse(3, 11));
+        assert_eq!(Ok(7), mod_inverse(-3, 11));
+        assert_eq!(Err(MathError::NoInverse), mod_inverse(4, 8));
+        assert_eq!(Err(MathError::InvalidModulus), mod_inverse(4, 0));
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(Ok((23, 105)), crt([(2, 3), (3, 5), (2, 7)]));
+        // moduli sharing a factor
+        assert_eq!(Ok((4, 6)), crt([(0, 2), (1, 3), (4, 6)]));
+        assert_eq!(Err(MathError::NoSolution), crt([(0, 2), (1, 4)]));
+        assert_eq!(Ok((0, 1)), crt([]));
+
+        let large = (1i128 << 62) - 57;
+        assert_eq!(
+            Err(MathError::Overflow),
+            crt([(1, large), (2, large - 2), (3, large - 4)])
+        );
+        let (x, m) = crt_big(
+            [(1, large), (2, large - 2), (3, large - 4)]
+                .map(|(r, m)| (BigInt::from(r), BigInt::from(m))),
+        )
+        .expect("solvable");
+        assert_eq!(BigInt::from(1), &x % BigInt::from(large));
+        assert_eq!(BigInt::from(2), &x % BigInt::from(large - 2));
+        assert!(x < m);
+    }
+}
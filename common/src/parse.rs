@@ -0,0 +1,143 @@
+//! Errors for puzzle input that fails to parse, pointing at the line and
+//! column where things went wrong.
+
+use nom::error::ErrorKind;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line of the failure
+    pub line: usize,
+    /// 1-based column of the failure, counted in characters
+    pub column: usize,
+    /// the whole line the failure is on
+    pub snippet: String,
+    /// what the parser wanted to find there
+    pub expected: String,
+}
+
+impl ParseError {
+    /// An error at byte `offset` into `input`.
+    pub fn at(input: &str, offset: usize, expected: impl Into<String>) -> Self {
+        let mut offset = offset.min(input.len());
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            snippet: input[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// An error at the start of `rest`, which must be a slice of `input`
+    /// like the remaining input nom hands back.
+    pub fn within(input: &str, rest: &str, expected: impl Into<String>) -> Self {
+        let offset = (rest.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|&offset| offset <= input.len())
+            .unwrap_or(input.len());
+        Self::at(input, offset, expected)
+    }
+
+    /// Locate a nom error in the `input` it was parsing.
+    pub fn from_nom(input: &str, err: nom::Err<nom::error::Error<&str>>) -> Self {
+        match err {
+            nom::Err::Incomplete(_) => Self::at(input, input.len(), "more input"),
+            nom::Err::Error(e) | nom::Err::Failure(e) => {
+                Self::within(input, e.input, describe(e.code))
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // keep tabs so the caret lines up however they're rendered
+        let padding: String = self
+            .snippet
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        write!(
+            f,
+            "line {}, column {}: expected {}\n{}\n{}^",
+            self.line, self.column, self.expected, self.snippet, padding
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn describe(kind: ErrorKind) -> String {
+    match kind {
+        ErrorKind::Tag => "specific text",
+        ErrorKind::Char => "a specific character",
+        ErrorKind::OneOf => "one of the allowed characters",
+        ErrorKind::Digit => "a number",
+        ErrorKind::Alpha => "letters",
+        ErrorKind::AlphaNumeric => "letters or digits",
+        ErrorKind::Space | ErrorKind::MultiSpace => "whitespace",
+        ErrorKind::CrLf => "a line break",
+        ErrorKind::SeparatedList | ErrorKind::Many1 => "at least one item",
+        ErrorKind::MapRes => "a valid value",
+        ErrorKind::Eof => "end of input",
+        kind => return kind.description().to_lowercase(),
+    }
+    .to_string()
+}
+
+/// Finish a nom parse of the whole of `input`: failures are located in
+/// `input`, and anything but whitespace left over is an error too.
+pub fn finish<'a, T>(input: &'a str, result: nom::IResult<&'a str, T>) -> Result<T, ParseError> {
+    let (rest, value) = result.map_err(|e| ParseError::from_nom(input, e))?;
+    if !rest.trim().is_empty() {
+        return Err(ParseError::within(input, rest.trim_start(), "end of input"));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::{character::complete, multi::separated_list1};
+
+    fn numbers(input: &str) -> nom::IResult<&str, Vec<u32>> {
+        separated_list1(complete::char(' '), complete::u32)(input)
+    }
+
+    #[test]
+    fn test_at() {
+        let err = ParseError::at("ab\ncdé f\ng", 7, "a number");
+        assert_eq!(2, err.line);
+        assert_eq!(4, err.column);
+        assert_eq!("cdé f", err.snippet);
+        assert_eq!(
+            "line 2, column 4: expected a number\ncdé f\n   ^",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_from_nom() {
+        let input = "1 2 3\nx 4";
+        let err = finish(input, numbers(&input[6..])).expect_err("not a number");
+        assert_eq!((2, 1), (err.line, err.column));
+        assert_eq!("a number", err.expected);
+
+        let err = finish(input, numbers(input)).expect_err("trailing input");
+        assert_eq!((2, 1), (err.line, err.column));
+        assert_eq!("end of input", err.expected);
+
+        assert_eq!(Ok(vec![1, 2]), finish("1 2\n", numbers("1 2\n")));
+    }
+}
@@ -0,0 +1,346 @@
+//! Reading puzzle pages: the description as Markdown, and the worked
+//! examples with their answers.
+
+use crate::examples::Example;
+
+enum Token<'a> {
+    Open(&'a str, &'a str),
+    Close(&'a str),
+    Text(&'a str),
+}
+
+/// Split html into tags and the text between them. Good enough for the
+/// well-formed pages the site serves, not for html in general.
+fn tokens(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+        let Some(end) = rest[start..].find('>') else {
+            tokens.push(Token::Text(&rest[start..]));
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim_end_matches('/');
+        rest = &rest[start + end + 1..];
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(name.trim()));
+        } else if !tag.starts_with('!') {
+            let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            tokens.push(Token::Open(name, attrs));
+        }
+    }
+    tokens
+}
+
+fn attr<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let start = attrs.find(&format!("{name}=\""))? + name.len() + 2;
+    let end = start + attrs[start..].find('"')?;
+    Some(&attrs[start..end])
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// The inner html of every `<article>` on a puzzle page: part one, and part
+/// two once it's unlocked.
+pub fn articles(page: &str) -> Vec<&str> {
+    let mut articles = vec![];
+    let mut rest = page;
+    while let Some(start) = rest.find("<article") {
+        let Some(open_end) = rest[start..].find('>') else {
+            break;
+        };
+        let body_start = start + open_end + 1;
+        let Some(len) = rest[body_start..].find("</article>") else {
+            break;
+        };
+        articles.push(&rest[body_start..body_start + len]);
+        rest = &rest[body_start + len..];
+    }
+    articles
+}
+
+/// Convert a puzzle `<article>` to Markdown. Links relative to the site are
+/// made absolute against `page_url`.
+pub fn html_to_markdown(html: &str, base_url: &str, page_url: &str) -> String {
+    let mut out = String::new();
+    let mut heading = None;
+    let mut in_pre = false;
+    let mut in_code = false;
+    let mut links = vec![];
+    for token in tokens(html) {
+        match token {
+            Token::Open("h2", _) => {
+                out.push_str("\n\n");
+                heading = Some(out.len());
+            }
+            Token::Close("h2") => {
+                if let Some(start) = heading.take() {
+                    let title = out[start..].trim().trim_matches('-').trim().to_string();
+                    out.truncate(start);
+                    let level = if title.starts_with("Day") { "#" } else { "##" };
+                    out.push_str(&format!("{level} {title}\n\n"));
+                }
+            }
+            Token::Open("p", _) | Token::Open("ul", _) => out.push_str("\n\n"),
+            Token::Close("p") | Token::Close("ul") => out.push_str("\n\n"),
+            Token::Open("li", _) => out.push_str("- "),
+            Token::Close("li") => out.push('\n'),
+            Token::Open("pre", _) => {
+                out.push_str("\n\n```\n");
+                in_pre = true;
+            }
+            Token::Close("pre") => {
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                out.push_str("```\n\n");
+                in_pre = false;
+            }
+            Token::Open("code", _) | Token::Close("code") if !in_pre => {
+                out.push('`');
+                in_code = matches!(token, Token::Open(..));
+            }
+            Token::Open("em", _) | Token::Close("em") if !in_pre && !in_code => out.push('*'),
+            Token::Open("a", attrs) => {
+                let href = attr(attrs, "href").unwrap_or_default();
+                let href = if href.starts_with("http") {
+                    href.to_string()
+                } else if href.starts_with('/') {
+                    format!("{base_url}{href}")
+                } else {
+                    format!(
+                        "{}/{href}",
+                        page_url.rsplit_once('/').map_or(page_url, |(dir, _)| dir)
+                    )
+                };
+                links.push(href);
+                out.push('[');
+            }
+            Token::Close("a") => {
+                let href = links.pop().unwrap_or_default();
+                out.push_str(&format!("]({href})"));
+            }
+            Token::Text(text) => {
+                let text = decode_entities(text);
+                if in_pre {
+                    out.push_str(&text);
+                } else if in_code {
+                    out.push_str(&text.replace('\n', " "));
+                } else {
+                    out.push_str(&text.replace('\n', " ").replace('*', "\\*"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // squash the blank lines left by nested blocks, keeping examples as is
+    let mut markdown = String::new();
+    let mut blank = false;
+    let mut fenced = false;
+    for line in out.lines() {
+        if line == "```" {
+            fenced = !fenced;
+        } else if fenced {
+            markdown.push_str(line);
+            markdown.push('\n');
+            continue;
+        }
+        if line.trim().is_empty() {
+            blank = true;
+            continue;
+        }
+        if blank && !markdown.is_empty() {
+            markdown.push('\n');
+        }
+        blank = false;
+        markdown.push_str(line.trim());
+        markdown.push('\n');
+    }
+    markdown
+}
+
+/// The worked example of a puzzle `<article>`: the first `<pre><code>`
+/// block, and its answer, the last emphasized code before the next
+/// `<pre>` that follows an answer. Pages show illustrations in `<pre>`
+/// blocks too, so those are skipped until the example has an answer, and
+/// a later example starts once it has. Articles without a `<pre>`, like
+/// most part twos, only have an answer.
+fn example(html: &str) -> (Option<String>, Option<String>) {
+    let mut input = None;
+    let mut answer = None;
+    let mut in_pre = false;
+    let mut in_code = false;
+    let mut in_em = false;
+    let mut text = String::new();
+    for token in tokens(html) {
+        match token {
+            Token::Open("pre", _) if input.is_some() && answer.is_some() => break,
+            Token::Open("pre", _) => {
+                // anything emphasized before the example isn't its answer
+                if input.is_none() {
+                    answer = None;
+                }
+                in_pre = true;
+                text.clear();
+            }
+            Token::Close("pre") => {
+                in_pre = false;
+                if input.is_none() && !text.trim().is_empty() {
+                    input = Some(text.clone());
+                }
+            }
+            Token::Open("code", _) => in_code = true,
+            Token::Close("code") => in_code = false,
+            Token::Open("em", _) if in_code && !in_pre => {
+                in_em = true;
+                text.clear();
+            }
+            Token::Close("em") if in_em => {
+                in_em = false;
+                answer = Some(answer_value(&text));
+            }
+            Token::Text(t) if in_pre || in_em => text.push_str(&decode_entities(t)),
+            _ => {}
+        }
+    }
+    (input, answer)
+}
+
+/// Answers are often given as the sum that makes them, `29 + 83 = 112`, so
+/// keep only what follows the last `=`.
+fn answer_value(text: &str) -> String {
+    match text.rsplit_once('=') {
+        Some((_, result)) => result.trim().to_string(),
+        None => text.trim().to_string(),
+    }
+}
+
+/// The example of each part on a puzzle page, in order. Part two usually
+/// reuses part one's example without repeating it, so it falls back to
+/// that input.
+pub fn examples(page: &str) -> Vec<Option<Example>> {
+    let mut previous_input = None;
+    articles(page)
+        .into_iter()
+        .map(|article| {
+            let (input, answer) = example(article);
+            let input = input.or(previous_input.clone())?;
+            previous_input = Some(input.clone());
+            Some(Example {
+                input,
+                answer: answer?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = include_str!("../../mock-aoc/fixtures/2023/01/puzzle.html");
+
+    #[test]
+    fn test_markdown() {
+        let articles = articles(PAGE);
+        assert_eq!(2, articles.len());
+        let markdown = html_to_markdown(
+            articles[1],
+            "https://adventofcode.com",
+            "https://adventofcode.com/2023/day/1",
+        );
+        assert_eq!(
+            "## Part Two
+
+Your calculation isn't quite right. It looks like some of the digits are actually *spelled out with letters*: `one`, `two`, and so on also count as valid \"digits\".
+
+For example:
+
+```
+two1nine
+eightwothree
+abcone2threexyz
+```
+
+Adding these together produces `29 + 83 + 13 = 125`.
+
+See [the about page](https://adventofcode.com/2023/about) & [your input](https://adventofcode.com/2023/day/1/input).
+",
+            markdown
+        );
+        assert!(html_to_markdown(articles[0], "", "").starts_with("# Day 1: Trebuchet?!\n\n"));
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(
+            vec![
+                Some(Example {
+                    input: "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n".to_string(),
+                    answer: "142".to_string(),
+                }),
+                Some(Example {
+                    input: "two1nine\neightwothree\nabcone2threexyz\n".to_string(),
+                    answer: "125".to_string(),
+                }),
+            ],
+            examples(PAGE)
+        );
+    }
+
+    #[test]
+    fn test_example_pairing() {
+        // an illustration before the answer, and emphasized intermediate
+        // values before the final one
+        let article = "<p>Find the <code><em>lowest</em></code> one:</p>\
+            <pre><code>seeds\n</code></pre><p>Each seed maps to a soil:</p>\
+            <pre><code>79 81\n</code></pre><p>Seed <code><em>79</em></code> gets to \
+            <code><em>82</em></code>, so the lowest is <code><em>35</em></code>.</p>";
+        assert_eq!(
+            (Some("seeds\n".to_string()), Some("35".to_string())),
+            example(article)
+        );
+
+        // a second example, with its own answer
+        let article =
+            "<pre><code>one\n</code></pre><p>That takes <code><em>2</em></code> steps.</p>\
+            <pre><code>two\n</code></pre><p>This takes <code><em>6</em></code> steps.</p>";
+        assert_eq!(
+            (Some("one\n".to_string()), Some("2".to_string())),
+            example(article)
+        );
+
+        // part two reusing part one's example
+        let page = "<article><pre><code>a\n</code></pre><code><em>1</em></code></article>\
+            <article><p>Now it's <code><em>1 + 2 = 3</em></code></p></article>";
+        assert_eq!(
+            vec![
+                Some(Example {
+                    input: "a\n".to_string(),
+                    answer: "1".to_string()
+                }),
+                Some(Example {
+                    input: "a\n".to_string(),
+                    answer: "3".to_string()
+                }),
+            ],
+            examples(page)
+        );
+        assert_eq!(vec![None], examples("<article><p>No example</p></article>"));
+    }
+}
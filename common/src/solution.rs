@@ -0,0 +1,137 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for Part {
+    type Error = anyhow::Error;
+
+    fn try_from(n: u8) -> Result<Self, Self::Error> {
+        Ok(match n {
+            1 => Part::One,
+            2 => Part::Two,
+            n => anyhow::bail!("part `{n}` must be 1 or 2"),
+        })
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+/// One part of one day's puzzle.
+///
+/// Implemented on a unit struct per part so that runners can hold every
+/// solution in a single registry, see [`DynSolution`].
+pub trait Solution {
+    /// The puzzle input after parsing. May borrow from the raw input.
+    type Input<'a>;
+
+    const DAY: u8;
+    const PART: Part;
+    const TITLE: &'static str;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>>;
+
+    fn solve(input: &Self::Input<'_>) -> anyhow::Result<String>;
+}
+
+/// The answer to a solution along with how long each phase took.
+#[derive(Debug)]
+pub struct Report {
+    pub answer: String,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+/// Object safe view of a [`Solution`], implemented for every solution.
+pub trait DynSolution: Sync {
+    fn day(&self) -> u8;
+
+    fn part(&self) -> Part;
+
+    fn title(&self) -> &'static str;
+
+    /// Parse and solve `input`, timing each phase separately.
+    fn run(&self, input: &str) -> anyhow::Result<Report>;
+}
+
+impl<S: Solution + Sync> DynSolution for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn part(&self) -> Part {
+        S::PART
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn run(&self, input: &str) -> anyhow::Result<Report> {
+        let start = Instant::now();
+        let parsed = S::parse(input)?;
+        let parse = start.elapsed();
+
+        let start = Instant::now();
+        let answer = S::solve(&parsed)?;
+        let solve = start.elapsed();
+
+        Ok(Report {
+            answer,
+            parse,
+            solve,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Echo;
+
+    impl Solution for Echo {
+        type Input<'a> = Vec<&'a str>;
+
+        const DAY: u8 = 1;
+        const PART: Part = Part::Two;
+        const TITLE: &'static str = "Echo";
+
+        fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+            Ok(input.lines().collect())
+        }
+
+        fn solve(input: &Self::Input<'_>) -> anyhow::Result<String> {
+            Ok(input.len().to_string())
+        }
+    }
+
+    #[test]
+    fn test_dyn_solution() -> anyhow::Result<()> {
+        let solution: &dyn DynSolution = &Echo;
+        assert_eq!(1, solution.day());
+        assert_eq!(Part::Two, solution.part());
+        assert_eq!("3", solution.run("a\nb\nc")?.answer);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+//! Finding the digits in a calibration line in one pass, with an
+//! Aho-Corasick automaton over the line's bytes.
+
+use std::collections::VecDeque;
+
+/// The digits as numerals.
+pub const NUMERALS: [&str; 9] = ["1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+/// The digits as the calibration document may spell them out.
+pub const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// Finds every occurrence of a set of patterns, each standing for a digit,
+/// in a single pass. Overlapping matches are all found, so `eightwo` is an
+/// eight and a two.
+#[derive(Debug, Clone)]
+pub struct DigitScanner {
+    /// the state after reading a byte in each state, already following the
+    /// failure links, so scanning never backtracks
+    next: Vec<[u8; 256]>,
+    /// the digit of the pattern ending in each state, if any
+    output: Vec<Option<u8>>,
+}
+
+impl DigitScanner {
+    /// Build a scanner for `patterns`, pairs of a pattern and the digit it
+    /// stands for. No pattern may contain another, so that matches come out
+    /// in the order they start in. Panics if there are more than 255 states.
+    pub fn new(patterns: &[(&str, u8)]) -> Self {
+        // the trie, with 0 meaning no edge as nothing leads back to the root
+        let mut next = vec![[0u8; 256]];
+        let mut output = vec![None];
+        for &(pattern, digit) in patterns {
+            let mut state = 0;
+            for &byte in pattern.as_bytes() {
+                if next[state][byte as usize] == 0 {
+                    next.push([0; 256]);
+                    output.push(None);
+                    next[state][byte as usize] =
+                        u8::try_from(next.len() - 1).expect("too many states for a u8");
+                }
+                state = next[state][byte as usize] as usize;
+            }
+            output[state] = Some(digit);
+        }
+
+        // fill in the missing edges breadth first, so each state's failure
+        // state, a strictly shallower one, is complete before it's needed
+        let mut fail = vec![0usize; next.len()];
+        let mut queue: VecDeque<usize> = next[0]
+            .iter()
+            .filter(|&&s| s != 0)
+            .map(|&s| s as usize)
+            .collect();
+        while let Some(state) = queue.pop_front() {
+            output[state] = output[state].or(output[fail[state]]);
+            let fallback = next[fail[state]];
+            for (edge, fallback) in next[state].iter_mut().zip(fallback) {
+                if *edge == 0 {
+                    *edge = fallback;
+                } else {
+                    fail[*edge as usize] = fallback as usize;
+                    queue.push_back(*edge as usize);
+                }
+            }
+        }
+        DigitScanner { next, output }
+    }
+
+    /// A scanner for digits written as `1` to `9` or spelled out.
+    pub fn spelled() -> Self {
+        let patterns: Vec<(&str, u8)> = (1..=9)
+            .zip(NUMERALS)
+            .chain((1..=9).zip(WORDS))
+            .map(|(digit, pattern)| (pattern, digit))
+            .collect();
+        Self::new(&patterns)
+    }
+
+    /// Every digit in `line`, in order.
+    pub fn digits<'a>(&'a self, line: &'a [u8]) -> impl Iterator<Item = u8> + 'a {
+        line.iter()
+            .scan(0usize, |state, &byte| {
+                *state = self.next[*state][byte as usize] as usize;
+                Some(self.output[*state])
+            })
+            .flatten()
+    }
+
+    /// The first digit and the last digit in `line` as a two digit number,
+    /// or `None` when there are no digits.
+    pub fn calibration_value(&self, line: &str) -> Option<u32> {
+        let mut digits = self.digits(line.as_bytes());
+        let first = digits.next()?;
+        let last = digits.last().unwrap_or(first);
+        Some(u32::from(first) * 10 + u32::from(last))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digits() {
+        let scanner = DigitScanner::spelled();
+        let digits = |line: &str| scanner.digits(line.as_bytes()).collect::<Vec<_>>();
+        assert_eq!(vec![8, 2], digits("eightwo"));
+        assert_eq!(vec![2, 1, 3, 4], digits("xtwone3four"));
+        assert_eq!(vec![9], digits("ninine"));
+        assert_eq!(vec![7, 9], digits("sevenine"));
+        assert_eq!(vec![8], digits("eeeight"));
+        assert_eq!(vec![1, 8, 2, 3, 4], digits("zoneight234"));
+        assert_eq!(Vec::<u8>::new(), digits("abc ∞ fiv"));
+    }
+
+    #[test]
+    fn test_calibration_value() {
+        let scanner = DigitScanner::spelled();
+        assert_eq!(Some(82), scanner.calibration_value("eightwo"));
+        assert_eq!(Some(77), scanner.calibration_value("7pqrst"));
+        assert_eq!(Some(76), scanner.calibration_value("7pqrstsixteen"));
+        assert_eq!(None, scanner.calibration_value("pqrst"));
+    }
+}
@@ -1,2 +1,3 @@
+pub mod digits;
 pub mod part1;
 pub mod part2;
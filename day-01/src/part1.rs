@@ -1,37 +1,65 @@
-pub fn run(input: &str) -> anyhow::Result<String> {
-    let lines = parse(input);
-    let mut sum = 0;
-    for line in lines {
-        let mut iter = line
-            .chars()
-            .filter(|c| c.is_digit(10))
-            .into_iter()
-            .peekable();
-        let first = iter.peek().cloned();
-        let last = iter.last();
-        let ns: String = [first.unwrap(), last.unwrap()].iter().collect();
-        let n = ns.parse::<i32>().unwrap();
-        sum += n
+use common::{ParseError, Part, Solution};
+
+pub struct Part1;
+
+impl Solution for Part1 {
+    type Input<'a> = Document<'a>;
+
+    const DAY: u8 = 1;
+    const PART: Part = Part::One;
+    const TITLE: &'static str = "Trebuchet?!";
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse(input))
+    }
+
+    fn solve(lines: &Self::Input<'_>) -> anyhow::Result<String> {
+        let mut sum = 0;
+        for (i, line) in lines.iter().enumerate() {
+            let mut digits = line
+                .bytes()
+                .filter(u8::is_ascii_digit)
+                .map(|b| u32::from(b - b'0'));
+            let first = digits.next().ok_or_else(|| missing_digit(i, line))?;
+            let last = digits.next_back().unwrap_or(first);
+            sum += first * 10 + last;
+        }
+        Ok(sum.to_string())
     }
-    Ok(sum.to_string())
 }
 
-fn parse(input: &str) -> Vec<&str> {
+pub fn run(input: &str) -> anyhow::Result<String> {
+    Part1::solve(&Part1::parse(input)?)
+}
+
+/// The calibration document, one entry per line.
+pub type Document<'a> = Vec<&'a str>;
+
+pub fn parse(input: &str) -> Document<'_> {
     input.lines().collect()
 }
 
+/// The error for the line at `index` in the document having no digits.
+pub fn missing_digit(index: usize, line: &str) -> ParseError {
+    ParseError {
+        line: index + 1,
+        ..ParseError::at(line, line.len(), "a digit")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    common::example_test!(run, 1);
+
     #[test]
-    fn test_run() -> anyhow::Result<()> {
-        let input = "1abc2
-pqr3stu8vwx
-a1b2c3d4e5f
-treb7uchet";
-        assert_eq!("142", run(input)?);
-
-        Ok(())
+    fn test_no_digits() {
+        let err = run("1abc2\npqrstu").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().expect("a parse error");
+        assert_eq!(
+            (2, 7, "a digit"),
+            (err.line, err.column, err.expected.as_str())
+        );
     }
 }
@@ -1,65 +1,51 @@
-const NUMS: &[&str] = &[
-    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-];
+use common::{Part, Solution};
 
-pub fn run(input: &str) -> anyhow::Result<String> {
-    let lines = parse(input);
-    let mut sum = 0;
-    for mut line in lines {
-        while !NUMS.iter().any(|s| line.starts_with(s))
-            && !line.chars().next().map(|c| c.is_numeric()).unwrap_or(true)
-        {
-            line = &line[1..]
-        }
-        while !NUMS.iter().any(|s| line.ends_with(s))
-            && !line.chars().last().map(|c| c.is_numeric()).unwrap_or(true)
-        {
-            line = &line[..line.len() - 1]
-        }
+use crate::{
+    digits::DigitScanner,
+    part1::{missing_digit, Document},
+};
 
-        let mut a = 0;
-        let mut b = 0;
+pub struct Part2;
 
-        for (x, s) in NUMS.iter().enumerate() {
-            if line.starts_with(s) {
-                a = x + 1
-            }
-            if line.ends_with(s) {
-                b = x + 1
-            }
-        }
-        let mut chars = line.chars().peekable();
-        if chars.peek().unwrap().is_numeric() {
-            a = chars.peek().unwrap().to_digit(10).unwrap() as usize;
-        }
-        let last = chars.last().unwrap();
-        if last.is_numeric() {
-            b = last.to_digit(10).unwrap() as usize;
+impl Solution for Part2 {
+    type Input<'a> = Document<'a>;
+
+    const DAY: u8 = 1;
+    const PART: Part = Part::Two;
+    const TITLE: &'static str = "Trebuchet?!";
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(crate::part1::parse(input))
+    }
+
+    fn solve(lines: &Self::Input<'_>) -> anyhow::Result<String> {
+        let scanner = DigitScanner::spelled();
+        let mut sum = 0;
+        for (i, line) in lines.iter().enumerate() {
+            sum += scanner
+                .calibration_value(line)
+                .ok_or_else(|| missing_digit(i, line))?;
         }
-        sum += a * 10 + b
+        Ok(sum.to_string())
     }
-    Ok(sum.to_string())
 }
 
-fn parse(input: &str) -> Vec<&str> {
-    input.lines().collect()
+pub fn run(input: &str) -> anyhow::Result<String> {
+    Part2::solve(&Part2::parse(input)?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_run() -> anyhow::Result<()> {
-        let input = "two1nine
-eightwothree
-abcone2threexyz
-xtwone3four
-4nineeightseven2
-zoneight234
-7pqrstsixteen";
-        assert_eq!("281", run(input)?);
+    common::example_test!(run, 2);
 
-        Ok(())
+    #[test]
+    fn test_no_digits() {
+        let err = run("two1nine\nabc").unwrap_err();
+        assert_eq!(
+            "line 2, column 4: expected a digit\nabc\n   ^",
+            err.to_string()
+        );
     }
 }
@@ -1,9 +1,10 @@
 use anyhow::{Context as _, Result};
+use common::{input, Part};
 use day_02::part2::run;
 
 fn main() -> Result<()> {
-    let file = include_str!("../../input2.txt");
-    let res = run(file).context("part 2")?;
+    let file = input::load(input::path(env!("CARGO_MANIFEST_DIR"), Part::Two))?;
+    let res = run(&file).context("part 2")?;
     println!("{}", res);
     Ok(())
 }
@@ -1,10 +1,11 @@
+use common::{ParseError, Part, Solution};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::str::FromStr;
 
 lazy_static! {
-    static ref GAME_RE: Regex = Regex::new(r"Game (\d+): ((:?[^;]+;?)+)").unwrap();
-    static ref COLOUR_RE: Regex = Regex::new(r"(\d+) (blue|green|red),?").unwrap();
+    static ref GAME_RE: Regex = Regex::new(r"^Game (\d+): (.*)$").unwrap();
+    static ref COLOUR_RE: Regex = Regex::new(r"^(\d+) (blue|green|red)$").unwrap();
 }
 
 #[derive(Default, Debug)]
@@ -22,7 +23,7 @@ impl Game {
                 acc.2.max(draw.green),
             )
         });
-        return min_balls.0 * min_balls.1 * min_balls.2;
+        min_balls.0 * min_balls.1 * min_balls.2
     }
 }
 
@@ -33,22 +34,30 @@ struct Draw {
     blue: u32,
 }
 
-impl FromStr for Game {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl Game {
+    /// Parse one game from `line`, a slice of `input` that errors point into.
+    fn parse_line(input: &str, line: &str) -> Result<Self, ParseError> {
         let mut game = Game::default();
-        let Some(m) = GAME_RE.captures(s) else {
-            anyhow::bail!("no match")
+        let Some(m) = GAME_RE.captures(line) else {
+            return Err(ParseError::within(input, line, "`Game <id>: <draws>`"));
         };
-        game.id = m[1].parse().unwrap();
-        let rest = &m[2];
-        let draws = rest.split(";");
+        let id = m.get(1).map_or("", |g| g.as_str());
+        game.id = id
+            .parse()
+            .map_err(|_| ParseError::within(input, id, "a game id"))?;
+        let rest = m.get(2).map_or("", |g| g.as_str());
+        let draws = rest.split("; ");
 
         for draw_s in draws {
             let mut draw = Draw::default();
-            for m in COLOUR_RE.captures_iter(draw_s) {
-                let n: u32 = m[1].parse().unwrap();
+            for piece in draw_s.split(", ") {
+                let Some(m) = COLOUR_RE.captures(piece) else {
+                    return Err(ParseError::within(input, piece, "`<count> red|green|blue`"));
+                };
+                let count = m.get(1).map_or("", |g| g.as_str());
+                let n: u32 = count
+                    .parse()
+                    .map_err(|_| ParseError::within(input, count, "a number of cubes"))?;
                 match &m[2] {
                     "blue" => draw.blue += n,
                     "green" => draw.green += n,
@@ -59,36 +68,77 @@ impl FromStr for Game {
             game.draws.push(draw);
         }
 
-        return Ok(game);
+        Ok(game)
     }
 }
+
+impl FromStr for Game {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Game::parse_line(s, s)
+    }
+}
+
+pub struct Part1;
+
+impl Solution for Part1 {
+    type Input<'a> = Vec<Game>;
+
+    const DAY: u8 = 2;
+    const PART: Part = Part::One;
+    const TITLE: &'static str = "Cube Conundrum";
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse(input)?)
+    }
+
+    fn solve(games: &Self::Input<'_>) -> anyhow::Result<String> {
+        Ok(games
+            .iter()
+            .filter(|g| {
+                g.draws
+                    .iter()
+                    .all(|d| d.red <= 12 && d.green <= 13 && d.blue <= 14)
+            })
+            .fold(0, |acc, g| acc + g.id)
+            .to_string())
+    }
+}
+
 pub fn run(input: &str) -> anyhow::Result<String> {
-    Ok(input
+    Part1::solve(&Part1::parse(input)?)
+}
+
+pub fn parse(input: &str) -> Result<Vec<Game>, ParseError> {
+    input
         .lines()
-        .map(|line| Game::from_str(line).unwrap())
-        .filter(|g| {
-            g.draws
-                .iter()
-                .all(|d| d.red <= 12 && d.green <= 13 && d.blue <= 14)
-        })
-        .fold(0, |acc, g| acc + g.id)
-        .to_string())
+        .map(|line| Game::parse_line(input, line))
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    common::example_test!(run, 1);
+
     #[test]
-    fn test_run() -> anyhow::Result<()> {
-        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
-";
-        assert_eq!("8", run(input)?);
-
-        Ok(())
+    fn test_parse_error() {
+        let err = parse("Game 1: 3 blue\nGame two: 4 red").expect_err("bad id");
+        assert_eq!((2, 1), (err.line, err.column));
+
+        let err = parse("Game 1: 99999999999 blue").expect_err("too many cubes");
+        assert_eq!((1, 9), (err.line, err.column));
+        assert_eq!("a number of cubes", err.expected);
+
+        let err = parse("Game 1: 3 blue, 4 rde; 2 purple").expect_err("bad colour");
+        assert_eq!((1, 17), (err.line, err.column));
+        assert_eq!("`<count> red|green|blue`", err.expected);
+
+        let err = parse("Game 1: 3 blue\nxx Game 3: 1 red junk").expect_err("bad prefix");
+        assert_eq!((2, 1), (err.line, err.column));
+        let err = parse("Game 3: 1 red junk").expect_err("trailing junk");
+        assert_eq!((1, 9), (err.line, err.column));
     }
 }
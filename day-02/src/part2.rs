@@ -1,29 +1,32 @@
-use std::str::FromStr;
+use common::{Part, Solution};
 
 use crate::part1::Game;
 
+pub struct Part2;
+
+impl Solution for Part2 {
+    type Input<'a> = Vec<Game>;
+
+    const DAY: u8 = 2;
+    const PART: Part = Part::Two;
+    const TITLE: &'static str = "Cube Conundrum";
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(crate::part1::parse(input)?)
+    }
+
+    fn solve(games: &Self::Input<'_>) -> anyhow::Result<String> {
+        Ok(games.iter().map(|g| g.power()).sum::<u32>().to_string())
+    }
+}
+
 pub fn run(input: &str) -> anyhow::Result<String> {
-    Ok(input
-        .lines()
-        .map(|line| Game::from_str(line).unwrap())
-        .map(|g| g.power())
-        .sum::<u32>()
-        .to_string())
+    Part2::solve(&Part2::parse(input)?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_run() -> anyhow::Result<()> {
-        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
-        assert_eq!("2286", run(input)?);
-
-        Ok(())
-    }
+    common::example_test!(run, 2);
 }
@@ -1,9 +1,10 @@
 use anyhow::{Context as _, Result};
+use common::{input, Part};
 use day_03::part1::run;
 
 fn main() -> Result<()> {
-    let file = include_str!("../../input1.txt");
-    let res = run(file).context("part 1")?;
+    let file = input::load(input::path(env!("CARGO_MANIFEST_DIR"), Part::One))?;
+    let res = run(&file).context("part 1")?;
     println!("{}", res);
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,255 @@
+use common::{
+    grid::{Pos, Span, Token},
+    math::MathError,
+    Grid, ParseError,
+};
+use std::{fmt, str::FromStr};
+
+/// A number drawn on the schematic. Only the ones next to a symbol are
+/// engine parts.
+pub type Number = Span<u64>;
+
+/// A symbol drawn on the schematic, always one cell wide.
+pub type Symbol = Span<char>;
+
+/// The engine schematic, as the numbers and symbols drawn on it in reading
+/// order. Everything else is `.`.
+#[derive(Debug, Clone)]
+pub struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+}
+
+impl Schematic {
+    pub fn numbers(&self) -> &[Number] {
+        &self.numbers
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// The numbers next to at least one symbol.
+    pub fn parts(&self) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .filter(|number| self.symbols_adjacent_to(number).next().is_some())
+    }
+
+    pub fn parts_adjacent_to<'a>(&'a self, symbol: &'a Symbol) -> impl Iterator<Item = &'a Number> {
+        self.numbers
+            .iter()
+            .filter(move |number| number.touches_span(symbol))
+    }
+
+    pub fn symbols_adjacent_to<'a>(
+        &'a self,
+        number: &'a Number,
+    ) -> impl Iterator<Item = &'a Symbol> {
+        self.symbols
+            .iter()
+            .filter(move |symbol| number.touches_span(symbol))
+    }
+
+    /// The symbols in `symbols` whose number of adjacent parts passes
+    /// `arity`, like `*` with exactly two parts for the puzzle's gears.
+    pub fn gears(&self, symbols: &str, arity: impl Fn(usize) -> bool) -> GearReport {
+        let gears = self
+            .symbols
+            .iter()
+            .filter(|symbol| symbols.contains(symbol.value))
+            .map(|symbol| Gear {
+                symbol: symbol.value,
+                pos: (symbol.row, symbol.cols.start),
+                parts: self
+                    .parts_adjacent_to(symbol)
+                    .map(|part| part.value)
+                    .collect(),
+            })
+            .filter(|gear| arity(gear.parts.len()))
+            .collect();
+        GearReport { gears }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gear {
+    pub symbol: char,
+    pub pos: Pos,
+    /// the adjacent part numbers, in reading order
+    pub parts: Vec<u64>,
+}
+
+impl Gear {
+    /// The product of the adjacent part numbers.
+    pub fn ratio(&self) -> Result<u64, MathError> {
+        self.parts
+            .iter()
+            .try_fold(1u64, |ratio, &part| ratio.checked_mul(part))
+            .ok_or(MathError::Overflow)
+    }
+}
+
+/// The gears found by [`Schematic::gears`], in reading order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GearReport {
+    pub gears: Vec<Gear>,
+}
+
+impl GearReport {
+    pub fn total_ratio(&self) -> Result<u64, MathError> {
+        self.gears.iter().try_fold(0u64, |total, gear| {
+            total.checked_add(gear.ratio()?).ok_or(MathError::Overflow)
+        })
+    }
+}
+
+/// One gear per line, counting rows and columns from 1 like [`ParseError`],
+/// e.g. `* at row 2, column 4: 467, 35`.
+impl fmt::Display for GearReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for gear in &self.gears {
+            let parts: Vec<String> = gear.parts.iter().map(u64::to_string).collect();
+            writeln!(
+                f,
+                "{} at row {}, column {}: {}",
+                gear.symbol,
+                gear.pos.0 + 1,
+                gear.pos.1 + 1,
+                parts.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Schematic {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut numbers = vec![];
+        let mut symbols = vec![];
+        for span in s.parse::<Grid<char>>()?.tokens('.')? {
+            match span.value {
+                Token::Number(value) => numbers.push(Span {
+                    value,
+                    row: span.row,
+                    cols: span.cols,
+                }),
+                Token::Symbol(value) => symbols.push(Span {
+                    value,
+                    row: span.row,
+                    cols: span.cols,
+                }),
+            }
+        }
+        Ok(Schematic { numbers, symbols })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    fn values<'a>(numbers: impl IntoIterator<Item = &'a Number>) -> Vec<u64> {
+        numbers.into_iter().map(|number| number.value).collect()
+    }
+
+    #[test]
+    fn test_parse() -> anyhow::Result<()> {
+        let schematic: Schematic = EXAMPLE.parse()?;
+        assert_eq!(10, schematic.numbers().len());
+        assert_eq!(
+            "*#*+$*",
+            schematic
+                .symbols()
+                .iter()
+                .map(|s| s.value)
+                .collect::<String>()
+        );
+        assert_eq!(
+            Number {
+                value: 617,
+                row: 4,
+                cols: 0..3
+            },
+            schematic.numbers()[4]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_adjacency() -> anyhow::Result<()> {
+        let schematic: Schematic = EXAMPLE.parse()?;
+        assert_eq!(
+            vec![467, 35, 633, 617, 592, 755, 664, 598],
+            values(schematic.parts())
+        );
+
+        let star = &schematic.symbols()[0];
+        assert_eq!(vec![467, 35], values(schematic.parts_adjacent_to(star)));
+        let number = &schematic.numbers()[1];
+        assert_eq!(114, number.value);
+        assert_eq!(0, schematic.symbols_adjacent_to(number).count());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_gears() -> anyhow::Result<()> {
+        let schematic: Schematic = EXAMPLE.parse()?;
+        let report = schematic.gears("*", |parts| parts == 2);
+        assert_eq!(
+            vec![
+                Gear {
+                    symbol: '*',
+                    pos: (1, 3),
+                    parts: vec![467, 35]
+                },
+                Gear {
+                    symbol: '*',
+                    pos: (8, 5),
+                    parts: vec![755, 598]
+                },
+            ],
+            report.gears
+        );
+        assert_eq!(467835, report.total_ratio()?);
+        assert_eq!(
+            "* at row 2, column 4: 467, 35\n* at row 9, column 6: 755, 598\n",
+            report.to_string()
+        );
+
+        let lonely = schematic.gears("*#+$", |parts| parts == 1);
+        assert_eq!(
+            vec![(3, 6), (4, 3), (5, 5), (8, 3)],
+            lonely.gears.iter().map(|gear| gear.pos).collect::<Vec<_>>()
+        );
+        assert!(schematic.gears("#", |parts| parts == 3).gears.is_empty());
+
+        let variant: Schematic = "12.3\n..#.\n.4..".parse()?;
+        let report = variant.gears("#", |parts| parts == 3);
+        assert_eq!(1, report.gears.len());
+        assert_eq!(144, report.total_ratio()?);
+
+        let huge: Schematic = "9999999999*9999999999".parse()?;
+        assert_eq!(
+            Err(MathError::Overflow),
+            huge.gears("*", |parts| parts == 2).total_ratio()
+        );
+
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
+use common::{parse, ParseError, Part, Solution};
 use nom::{
     bytes::complete::tag,
-    character::complete::{self, line_ending, space1},
+    character::complete::{self, space1},
     multi::separated_list1,
     sequence::{delimited, separated_pair, terminated},
     IResult,
@@ -8,7 +9,7 @@ use nom::{
 use std::collections::HashSet;
 
 #[derive(Debug)]
-struct Card {
+pub struct Card {
     #[allow(dead_code)]
     number: u32,
     winning: HashSet<u32>,
@@ -55,32 +56,40 @@ fn parse_card(line: &str) -> IResult<&str, Card> {
     ))
 }
 
-fn parse_cards(input: &str) -> IResult<&str, Vec<Card>> {
-    separated_list1(line_ending, parse_card)(input)
+pub struct Part1;
+
+impl Solution for Part1 {
+    type Input<'a> = Vec<Card>;
+
+    const DAY: u8 = 4;
+    const PART: Part = Part::One;
+    const TITLE: &'static str = "Scratchcards";
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse(input)?)
+    }
+
+    fn solve(cards: &Self::Input<'_>) -> anyhow::Result<String> {
+        Ok(cards.iter().map(|c| c.power()).sum::<u32>().to_string())
+    }
 }
 
 pub fn run(input: &str) -> anyhow::Result<String> {
-    let (_, cards) = parse_cards(input)
-        .map_err(|e| anyhow::anyhow!("parsing failed: {}", e.to_string()))?;
-    Ok(cards.iter().map(|c| c.power()).sum::<u32>().to_string())
+    Part1::solve(&Part1::parse(input)?)
+}
+
+pub fn parse(input: &str) -> Result<Vec<Card>, ParseError> {
+    input
+        .lines()
+        .map(|line| parse::finish(input, parse_card(line)))
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_run() -> anyhow::Result<()> {
-        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-        assert_eq!("13", run(input)?);
-
-        Ok(())
-    }
+    common::example_test!(run, 1);
 
     #[test]
     fn test_card() {
@@ -99,4 +108,16 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
         let input: &str = "Card  47: 25 62 88 36 24 10 15 90  8 23 |  4 54  9 80 42 78 32 19 21 65 86 26 28  7 96 71 48 64 36 95 69 44 89 34 57";
         let (_, _) = parse_card(input).expect("parse card");
     }
+
+    #[test]
+    fn test_parse_error() {
+        let input = "Card 1: 41 48 | 83 86
+Card 2: 13 x2 | 61 30";
+        let err = parse(input).expect_err("bad number");
+        assert_eq!((2, 12), (err.line, err.column));
+        assert_eq!("Card 2: 13 x2 | 61 30", err.snippet);
+
+        let err = parse("Card 1: 41 48 83 86").expect_err("no separator");
+        assert_eq!((1, 20), (err.line, err.column));
+    }
 }
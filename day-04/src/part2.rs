@@ -1,22 +1,23 @@
+use common::{math::MathError, parse, ParseError, Part, Solution};
 use std::collections::{BTreeMap, HashSet};
 
 use nom::{
     bytes::complete::tag,
-    character::complete::{self, line_ending, space1},
+    character::complete::{self, space1},
     multi::separated_list1,
     sequence::{delimited, separated_pair, terminated},
     IResult,
 };
 
 #[derive(Debug)]
-struct Card {
+pub struct Card {
     number: u32,
     winning: HashSet<u32>,
     have: HashSet<u32>,
 }
 
 #[derive(Debug)]
-struct CardState {
+pub struct CardState {
     // card number -> matches
     matches: BTreeMap<u32, u32>,
     // card number -> number of cards
@@ -26,7 +27,9 @@ struct CardState {
 }
 
 impl CardState {
-    fn round(&mut self) -> bool {
+    /// Scratch one copy of every card that still has any, returning whether
+    /// any copies are left. Slow, see [`CardState::total_cards`].
+    pub fn round(&mut self) -> bool {
         let nums: Vec<u32> = self.counts.keys().cloned().collect();
         for num in nums {
             if *self.counts.get(&num).expect("no count") == 0 {
@@ -49,12 +52,35 @@ impl CardState {
         if self.counts.iter().all(|(_, count)| *count == 0) {
             return false;
         }
-        return true;
+        true
+    }
+
+    /// Every card scratched so far plus every card the remaining ones will
+    /// win, in a single forward pass. Cards only ever win copies of later
+    /// cards, so a card's count is final by the time the pass reaches it.
+    pub fn total_cards(&self) -> Result<u64, MathError> {
+        let mut counts: BTreeMap<u32, u64> = self
+            .counts
+            .iter()
+            .map(|(&num, &count)| (num, count as u64))
+            .collect();
+        let mut total = self.score as u64;
+        for (&num, &matches) in &self.matches {
+            let count = counts.get(&num).copied().unwrap_or(0);
+            total = total.checked_add(count).ok_or(MathError::Overflow)?;
+            for won in num.saturating_add(1)..=num.saturating_add(matches) {
+                // like `round`, copies of cards past the end of the table are lost
+                if let Some(won) = counts.get_mut(&won) {
+                    *won = won.checked_add(count).ok_or(MathError::Overflow)?;
+                }
+            }
+        }
+        Ok(total)
     }
 }
 
-impl From<Vec<Card>> for CardState {
-    fn from(cards: Vec<Card>) -> Self {
+impl From<&[Card]> for CardState {
+    fn from(cards: &[Card]) -> Self {
         let mut matches: BTreeMap<u32, u32> = Default::default();
         let mut counts: BTreeMap<u32, u32> = Default::default();
         for card in cards {
@@ -99,32 +125,76 @@ fn parse_card(line: &str) -> IResult<&str, Card> {
     ))
 }
 
-fn parse_cards(input: &str) -> IResult<&str, Vec<Card>> {
-    separated_list1(line_ending, parse_card)(input)
+pub struct Part2;
+
+impl Solution for Part2 {
+    type Input<'a> = Vec<Card>;
+
+    const DAY: u8 = 4;
+    const PART: Part = Part::Two;
+    const TITLE: &'static str = "Scratchcards";
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse(input)?)
+    }
+
+    fn solve(cards: &Self::Input<'_>) -> anyhow::Result<String> {
+        let state = CardState::from(cards.as_slice());
+        Ok(state.total_cards()?.to_string())
+    }
 }
 
 pub fn run(input: &str) -> anyhow::Result<String> {
-    let (_, cards) =
-        parse_cards(input).map_err(|e| anyhow::anyhow!("parsing failed: {}", e.to_string()))?;
-    let mut state = CardState::from(cards);
-    while state.round() {}
-    Ok(state.score.to_string())
+    Part2::solve(&Part2::parse(input)?)
+}
+
+pub fn parse(input: &str) -> Result<Vec<Card>, ParseError> {
+    input
+        .lines()
+        .map(|line| parse::finish(input, parse_card(line)))
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    common::example_test!(run, 2);
+
+    fn state(matches: &[u32]) -> CardState {
+        CardState {
+            matches: (1..).zip(matches.iter().copied()).collect(),
+            counts: (1..=matches.len() as u32).map(|num| (num, 1)).collect(),
+            score: 0,
+        }
+    }
+
     #[test]
-    fn test_run() -> anyhow::Result<()> {
-        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-        assert_eq!("30", run(input)?);
+    fn test_total_cards_matches_rounds() -> anyhow::Result<()> {
+        for matches in [
+            vec![4, 2, 2, 1, 0, 0],
+            vec![0; 5],
+            vec![3, 3, 3, 3, 3, 3, 3],
+            vec![5, 1, 0, 7, 2, 2, 1, 0],
+        ] {
+            let linear = state(&matches).total_cards()?;
+            let mut rounds = state(&matches);
+            while rounds.round() {}
+            assert_eq!(rounds.score as u64, linear, "{matches:?}");
+
+            // picking up part way through the simulation gives the same total
+            let mut partial = state(&matches);
+            partial.round();
+            assert_eq!(linear, partial.total_cards()?, "{matches:?}");
+        }
 
         Ok(())
     }
+
+    #[test]
+    fn test_total_cards_overflow() {
+        // card n ends up with 2^(n-1) copies
+        let matches: Vec<u32> = (0..70).rev().collect();
+        assert_eq!(Err(MathError::Overflow), state(&matches).total_cards());
+    }
 }
@@ -1,3 +1,4 @@
+use common::{parse, ParseError, Part, Solution};
 use nom::{
     bytes::complete::tag,
     character::complete::{self, alpha1, line_ending, space0, space1},
@@ -7,7 +8,7 @@ use nom::{
 };
 
 #[derive(Debug)]
-struct Alminac {
+pub struct Alminac {
     seeds: Vec<u64>,
     maps: Vec<AlminacMap>,
 }
@@ -67,7 +68,7 @@ fn parse_map(input: &str) -> nom::IResult<&str, AlminacMap> {
     let (input, _) = separated_pair(alpha1, tag("-to-"), alpha1)(input)?;
     let (input, _) = terminated(tag(" map:"), line_ending)(input)?;
     let (input, mut mappings) = separated_list1(line_ending, parse_mapping)(input)?;
-    mappings.sort_by(|a, b| a.dst.partial_cmp(&b.dst).unwrap());
+    mappings.sort_by_key(|m| m.dst);
     Ok((input, AlminacMap(mappings)))
 }
 
@@ -86,54 +87,37 @@ fn parse_alminac(input: &str) -> nom::IResult<&str, Alminac> {
     Ok((input, Alminac { seeds, maps }))
 }
 
-pub fn run(input: &str) -> anyhow::Result<String> {
-    let (_, alminac) = parse_alminac(input).map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
-    Ok(alminac.find_lowest_location().to_string())
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Part1;
 
-    #[test]
-    fn test_run() -> anyhow::Result<()> {
-        let input = "seeds: 79 14 55 13
-
-seed-to-soil map:
-50 98 2
-52 50 48
+impl Solution for Part1 {
+    type Input<'a> = Alminac;
 
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
+    const DAY: u8 = 5;
+    const PART: Part = Part::One;
+    const TITLE: &'static str = "If You Give A Seed A Fertilizer";
 
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse(input)?)
+    }
 
-water-to-light map:
-88 18 7
-18 25 70
+    fn solve(alminac: &Self::Input<'_>) -> anyhow::Result<String> {
+        Ok(alminac.find_lowest_location().to_string())
+    }
+}
 
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
+pub fn run(input: &str) -> anyhow::Result<String> {
+    Part1::solve(&Part1::parse(input)?)
+}
 
-temperature-to-humidity map:
-0 69 1
-1 0 69
+pub fn parse(input: &str) -> Result<Alminac, ParseError> {
+    parse::finish(input, parse_alminac(input))
+}
 
-humidity-to-location map:
-60 56 37
-56 93 4";
-        assert_eq!("35", run(input)?);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        Ok(())
-    }
+    common::example_test!(run, 1);
 
     #[test]
     fn test_parse_seeds() {
@@ -1,3 +1,5 @@
+use common::{parse, ParseError, Part, Solution};
+use iset::IntervalMap;
 use nom::{
     bytes::complete::tag,
     character::complete::{self, alpha1, line_ending, space0, space1},
@@ -5,6 +7,7 @@ use nom::{
     multi::separated_list1,
     sequence::{separated_pair, terminated},
 };
+use std::ops::Range;
 
 #[derive(Debug)]
 struct SeedRange {
@@ -12,67 +15,59 @@ struct SeedRange {
     range: u64,
 }
 
-impl<'a> IntoIterator for &'a SeedRange {
-    type Item = u64;
-
-    type IntoIter = SeedRageIterator<'a>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        SeedRageIterator { inner: self, i: 0 }
-    }
-}
-
-struct SeedRageIterator<'a> {
-    inner: &'a SeedRange,
-    i: usize,
-}
-
-impl<'a> Iterator for SeedRageIterator<'a> {
-    type Item = u64;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if (self.i as u64) < self.inner.range {
-            self.i += 1;
-            Some(self.inner.seed + self.i as u64)
-        } else {
-            None
-        }
+impl SeedRange {
+    fn as_range(&self) -> Range<u64> {
+        self.seed..self.seed + self.range
     }
 }
 
 #[derive(Debug)]
-struct Alminac {
+pub struct Alminac {
     seeds: Vec<SeedRange>,
     maps: Vec<AlminacMap>,
 }
 
 impl Alminac {
-    // fn find_lowest_location(&self) -> u64 {
-    //     let mut maps = self.maps.iter().rev();
-    //     let location_map = maps.next().unwrap();
-
-    //     dbg!(location_map.possible_destinations().collect::<Vec<_>>());
-
-    //     for dst in location_map.possible_destinations() {
-    //         let mut mapping = dst;
-    //         for map in maps.clone() {
-    //             mapping = map.get_rev(mapping);
-    //         }
-    //         if self.seeds.iter().flatten().any(|seed| seed == mapping) {
-    //             return dst;
-    //         }
-    //     }
-    //     0
-    // }
     fn find_lowest_location(&self) -> u64 {
+        let mut ranges: Vec<Range<u64>> = self.seeds.iter().map(SeedRange::as_range).collect();
+        for map in &self.maps {
+            ranges = ranges
+                .into_iter()
+                .flat_map(|range| map.get_ranges(range))
+                .collect();
+        }
+        ranges.iter().map(|r| r.start).min().unwrap_or(u64::MAX)
+    }
+
+    /// The whole seed to location chain collapsed into a single map.
+    pub fn composed(&self) -> AlminacMap {
+        self.maps
+            .iter()
+            .fold(AlminacMap::new(vec![]), |acc, map| acc.compose(map))
+    }
+
+    /// Every seed listed in the almanac that lands on `location`.
+    pub fn seeds_for_location(&self, location: u64) -> Vec<u64> {
+        self.composed()
+            .get_rev(location)
+            .into_iter()
+            .filter(|seed| self.seeds.iter().any(|r| r.as_range().contains(seed)))
+            .collect()
+    }
+
+    /// Same as [`Alminac::find_lowest_location`], but found by intersecting
+    /// the seed ranges with each piece of the composed map instead of pushing
+    /// the seeds forward through every map.
+    pub fn find_lowest_location_rev(&self) -> u64 {
         let mut lowest = u64::MAX;
-        for seed in self.seeds.iter().flatten() {
-            let mut mapping = seed;
-            for map in &self.maps {
-                mapping = map.get(mapping);
-            }
-            if mapping < lowest {
-                lowest = mapping;
+        for segment in self.composed().mappings() {
+            let src = segment.src_range();
+            for seeds in self.seeds.iter().map(SeedRange::as_range) {
+                // each segment is increasing, so its lowest seed maps lowest
+                let start = seeds.start.max(src.start);
+                if start < seeds.end.min(src.end) {
+                    lowest = lowest.min(segment.get_unchecked(start));
+                }
             }
         }
         lowest
@@ -80,33 +75,158 @@ impl Alminac {
 }
 
 #[derive(Debug)]
-struct AlminacMap(Vec<Mapping>);
+pub struct AlminacMap {
+    mappings: Vec<Mapping>,
+    // src interval -> index into mappings
+    index: IntervalMap<u64, usize>,
+}
 
 impl AlminacMap {
-    fn get(&self, src: u64) -> u64 {
-        for map in &self.0 {
-            if let Some(x) = map.get(src) {
-                return x;
+    fn new(mappings: Vec<Mapping>) -> Self {
+        let mut index = IntervalMap::new();
+        for (i, mapping) in mappings.iter().enumerate() {
+            // iset panics on empty intervals
+            if mapping.range > 0 {
+                index.force_insert(mapping.src_range(), i);
             }
         }
-        src
+        AlminacMap { mappings, index }
+    }
+
+    pub fn mappings(&self) -> &[Mapping] {
+        &self.mappings
+    }
+
+    pub fn get(&self, src: u64) -> u64 {
+        self.index
+            .values_overlap(src)
+            .next()
+            .map_or(src, |&i| self.mappings[i].get_unchecked(src))
+    }
+
+    /// Every source that maps onto `dst`. A destination can be reached both
+    /// through a mapping and by an unmapped source passing straight through,
+    /// so there may be more than one.
+    pub fn get_rev(&self, dst: u64) -> Vec<u64> {
+        let mut srcs: Vec<u64> = self
+            .mappings
+            .iter()
+            .filter_map(|m| m.get_rev(dst))
+            .collect();
+        if self.index.values_overlap(dst).next().is_none() {
+            srcs.push(dst);
+        }
+        srcs.sort();
+        srcs.dedup();
+        srcs
+    }
+
+    /// Map a whole range of sources at once, splitting it wherever it crosses
+    /// a mapping boundary. Parts of `src` that no mapping covers map to
+    /// themselves. The output ranges are not sorted or merged.
+    fn get_ranges(&self, src: Range<u64>) -> Vec<Range<u64>> {
+        self.split(src).into_iter().map(|m| m.dst_range()).collect()
+    }
+
+    /// Cut `src` into pieces that each map linearly, in source order.
+    fn split(&self, src: Range<u64>) -> Vec<Mapping> {
+        let mut pieces = vec![];
+        if src.is_empty() {
+            return pieces;
+        }
+
+        // intervals come back sorted by start, so walk a cursor along src
+        let mut cursor = src.start;
+        for (interval, &i) in self.index.iter(src.clone()) {
+            if interval.start > cursor {
+                pieces.push(Mapping::identity(cursor..interval.start));
+            }
+            let overlap = interval.start.max(cursor)..interval.end.min(src.end);
+            if !overlap.is_empty() {
+                pieces.push(Mapping {
+                    src: overlap.start,
+                    dst: self.mappings[i].get_unchecked(overlap.start),
+                    range: overlap.end - overlap.start,
+                });
+                cursor = overlap.end;
+            }
+        }
+        if cursor < src.end {
+            pieces.push(Mapping::identity(cursor..src.end));
+        }
+        pieces
+    }
+
+    /// Apply `self` then `next` as one map. The result covers every source
+    /// in `0..u64::MAX` explicitly, identity pieces included.
+    pub fn compose(&self, next: &AlminacMap) -> AlminacMap {
+        let mut mappings: Vec<Mapping> = vec![];
+        for segment in self.split(0..u64::MAX) {
+            for piece in next.split(segment.dst_range()) {
+                let mapping = Mapping {
+                    src: segment.src + (piece.src - segment.dst),
+                    dst: piece.dst,
+                    range: piece.range,
+                };
+                match mappings.last_mut() {
+                    // merge neighbours that continue the same line
+                    Some(last)
+                        if last.src + last.range == mapping.src
+                            && last.dst + last.range == mapping.dst =>
+                    {
+                        last.range += mapping.range
+                    }
+                    _ => mappings.push(mapping),
+                }
+            }
+        }
+        AlminacMap::new(mappings)
     }
 }
 
-#[derive(Debug)]
-struct Mapping {
-    src: u64,
-    dst: u64,
-    range: u64,
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Mapping {
+    pub src: u64,
+    pub dst: u64,
+    pub range: u64,
 }
 
 impl Mapping {
-    fn get(&self, src: u64) -> Option<u64> {
-        if src >= self.src && src < self.src + self.range {
-            return Some((src - self.src) + self.dst);
+    fn identity(range: Range<u64>) -> Self {
+        Mapping {
+            src: range.start,
+            dst: range.start,
+            range: range.end - range.start,
+        }
+    }
+
+    pub fn src_range(&self) -> Range<u64> {
+        self.src..self.src + self.range
+    }
+
+    pub fn dst_range(&self) -> Range<u64> {
+        self.dst..self.dst + self.range
+    }
+
+    pub fn get(&self, src: u64) -> Option<u64> {
+        if self.src_range().contains(&src) {
+            return Some(self.get_unchecked(src));
         }
         None
     }
+
+    pub fn get_rev(&self, dst: u64) -> Option<u64> {
+        if self.dst_range().contains(&dst) {
+            return Some((dst - self.dst) + self.src);
+        }
+        None
+    }
+
+    /// Map `src` without checking that it falls inside this mapping. Also
+    /// valid for the exclusive end of the source range.
+    fn get_unchecked(&self, src: u64) -> u64 {
+        (src - self.src) + self.dst
+    }
 }
 
 fn parse_range(input: &str) -> nom::IResult<&str, SeedRange> {
@@ -123,8 +243,8 @@ fn parse_map(input: &str) -> nom::IResult<&str, AlminacMap> {
     let (input, _) = separated_pair(alpha1, tag("-to-"), alpha1)(input)?;
     let (input, _) = terminated(tag(" map:"), line_ending)(input)?;
     let (input, mut mappings) = separated_list1(line_ending, parse_mapping)(input)?;
-    mappings.sort_by(|a, b| a.dst.partial_cmp(&b.dst).unwrap());
-    Ok((input, AlminacMap(mappings)))
+    mappings.sort_by_key(|m| m.dst);
+    Ok((input, AlminacMap::new(mappings)))
 }
 
 fn parse_mapping(input: &str) -> nom::IResult<&str, Mapping> {
@@ -142,17 +262,61 @@ fn parse_alminac(input: &str) -> nom::IResult<&str, Alminac> {
     Ok((input, Alminac { seeds, maps }))
 }
 
+pub struct Part2;
+
+impl Solution for Part2 {
+    type Input<'a> = Alminac;
+
+    const DAY: u8 = 5;
+    const PART: Part = Part::Two;
+    const TITLE: &'static str = "If You Give A Seed A Fertilizer";
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse(input)?)
+    }
+
+    fn solve(alminac: &Self::Input<'_>) -> anyhow::Result<String> {
+        Ok(alminac.find_lowest_location().to_string())
+    }
+}
+
 pub fn run(input: &str) -> anyhow::Result<String> {
-    let (_, alminac) = parse_alminac(input).map_err(|e| anyhow::anyhow!("{}", e.to_string()))?;
-    Ok(alminac.find_lowest_location().to_string())
+    Part2::solve(&Part2::parse(input)?)
+}
+
+pub fn parse(input: &str) -> Result<Alminac, ParseError> {
+    parse::finish(input, parse_alminac(input))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    common::example_test!(run, 2);
+
+    #[test]
+    fn test_parse_seeds() {
+        let input = "seeds: 79 14 55 13";
+        let (input, seeds) = parse_seeds(input).expect("should parse");
+        assert_eq!(2, seeds.len());
+        assert_eq!("", input);
+    }
+
+    #[test]
+    fn test_parse_map() {
+        let input = "soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15";
+        let (_, map) = parse_map(input).expect("should parse");
+        assert_eq!(3, map.mappings.len());
+        assert_eq!(0, map.mappings[0].dst);
+        assert_eq!(15, map.mappings[0].src);
+        assert_eq!(37, map.mappings[0].range);
+    }
+
     #[test]
-    fn test_run() -> anyhow::Result<()> {
+    fn test_parse_alminac() {
         let input = "seeds: 79 14 55 13
 
 seed-to-soil map:
@@ -186,34 +350,35 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
-        assert_eq!("46", run(input)?);
-
-        Ok(())
+        let (_, _) = parse_alminac(input).expect("should parse");
     }
 
     #[test]
-    fn test_parse_seeds() {
-        let input = "seeds: 79 14 55 13";
-        let (input, seeds) = parse_seeds(input).expect("should parse");
-        assert_eq!(2, seeds.len());
-        assert_eq!("", input);
+    fn test_get_ranges() {
+        let map = AlminacMap::new(vec![
+            Mapping {
+                src: 10,
+                dst: 100,
+                range: 5,
+            },
+            Mapping {
+                src: 20,
+                dst: 0,
+                range: 10,
+            },
+        ]);
+
+        let mut ranges = map.get_ranges(5..25);
+        ranges.sort_by_key(|r| r.start);
+        assert_eq!(vec![0..5, 5..10, 15..20, 100..105], ranges);
+
+        assert_eq!(vec![102..103], map.get_ranges(12..13));
+        assert_eq!(vec![30..40], map.get_ranges(30..40));
+        assert!(map.get_ranges(7..7).is_empty());
     }
 
     #[test]
-    fn test_parse_map() {
-        let input = "soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15";
-        let (_, map) = parse_map(input).expect("should parse");
-        assert_eq!(3, map.0.len());
-        assert_eq!(0, map.0[0].dst);
-        assert_eq!(15, map.0[0].src);
-        assert_eq!(37, map.0[0].range);
-    }
-
-    #[test]
-    fn test_parse_alminac() {
+    fn test_matches_brute_force() -> anyhow::Result<()> {
         let input = "seeds: 79 14 55 13
 
 seed-to-soil map:
@@ -247,6 +412,59 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
-        let (_, _) = parse_alminac(input).expect("should parse");
+        let alminac = parse(input)?;
+        let brute_force = alminac
+            .seeds
+            .iter()
+            .flat_map(SeedRange::as_range)
+            .map(|seed| {
+                alminac.maps.iter().fold(seed, |src, map| {
+                    map.mappings
+                        .iter()
+                        .find(|m| m.src_range().contains(&src))
+                        .map_or(src, |m| m.get_unchecked(src))
+                })
+            })
+            .min()
+            .expect("seeds");
+        assert_eq!(brute_force, alminac.find_lowest_location());
+        assert_eq!(brute_force, alminac.find_lowest_location_rev());
+
+        let composed = alminac.composed();
+        for seed in alminac.seeds.iter().flat_map(SeedRange::as_range) {
+            let chained = alminac.maps.iter().fold(seed, |src, map| map.get(src));
+            assert_eq!(chained, composed.get(seed), "seed {seed}");
+        }
+        assert_eq!(vec![82], alminac.seeds_for_location(46));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_rev() {
+        let map = AlminacMap::new(vec![
+            Mapping {
+                src: 98,
+                dst: 50,
+                range: 2,
+            },
+            Mapping {
+                src: 50,
+                dst: 52,
+                range: 48,
+            },
+        ]);
+        assert_eq!(vec![98], map.get_rev(50));
+        // 60 is itself mapped away, so only 58 reaches it
+        assert_eq!(vec![58], map.get_rev(60));
+        // 10 passes straight through
+        assert_eq!(vec![10], map.get_rev(10));
+        // 99 comes from 97 and from nothing else, since 99 is itself mapped
+        assert_eq!(vec![97], map.get_rev(99));
+        // 100 is unmapped, and is also hit by nothing else
+        assert_eq!(vec![100], map.get_rev(100));
+        for src in 0..110 {
+            assert!(map.get_rev(map.get(src)).contains(&src), "src {src}");
+        }
     }
 }
@@ -1,3 +1,4 @@
+use common::{parse, ParseError, Part, Solution};
 use nom::{
     bytes::complete::tag,
     character::{
@@ -10,29 +11,54 @@ use nom::{
     IResult,
 };
 
-struct BoatRace {
-    distance: u32,
-    time: u32,
+#[derive(Debug)]
+pub struct BoatRace {
+    pub distance: u64,
+    pub time: u64,
 }
 
 impl BoatRace {
-    fn ways(&self) -> u32 {
-        (1..self.time)
-            .map(|button_seconds| self.race(button_seconds))
-            .filter(|&distance| distance > self.distance)
-            .count() as u32
+    /// Count the button times that beat the record, i.e. the integers `b`
+    /// with `b * (time - b) > distance`.
+    ///
+    /// The winners lie strictly between the roots of `b^2 - time*b +
+    /// distance`, so rather than racing every `b` we estimate the lower root
+    /// with an integer square root, nudge it up to the first real winner and
+    /// mirror it for the upper bound.
+    pub fn ways(&self) -> u64 {
+        let time = self.time as u128;
+        let Some(discriminant) = (time * time).checked_sub(4 * self.distance as u128) else {
+            return 0;
+        };
+        // the estimate is never past the first winner, and at most one or two
+        // below it. stepping also settles perfect squares, where the root
+        // itself only ties the record
+        let mut lo = ((time - discriminant.isqrt()) / 2) as u64;
+        while lo <= self.time / 2 && !self.wins(lo) {
+            lo += 1;
+        }
+        if lo > self.time / 2 {
+            return 0;
+        }
+        // b * (time - b) is symmetric around time / 2
+        let hi = self.time - lo;
+        hi - lo + 1
     }
 
-    fn race(&self, button_seconds: u32) -> u32 {
+    fn wins(&self, button_seconds: u64) -> bool {
+        self.race(button_seconds) > self.distance as u128
+    }
+
+    fn race(&self, button_seconds: u64) -> u128 {
         let remaining = self.time - button_seconds;
-        button_seconds * remaining
+        button_seconds as u128 * remaining as u128
     }
 }
 
-fn parse_nums(input: &str) -> IResult<&str, Vec<u32>> {
+fn parse_nums(input: &str) -> IResult<&str, Vec<u64>> {
     let (input, _) = terminated(alpha0, tag(":"))(input)?;
     let (input, _) = space1(input)?;
-    separated_list1(space1, complete::u32)(input)
+    separated_list1(space1, complete::u64)(input)
 }
 
 fn parse_boat_races(input: &str) -> IResult<&str, Vec<BoatRace>> {
@@ -48,23 +74,37 @@ fn parse_boat_races(input: &str) -> IResult<&str, Vec<BoatRace>> {
     ))
 }
 
+pub struct Part1;
+
+impl Solution for Part1 {
+    type Input<'a> = Vec<BoatRace>;
+
+    const DAY: u8 = 6;
+    const PART: Part = Part::One;
+    const TITLE: &'static str = "Wait For It";
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse(input)?)
+    }
+
+    fn solve(races: &Self::Input<'_>) -> anyhow::Result<String> {
+        Ok(races.iter().map(|r| r.ways()).product::<u64>().to_string())
+    }
+}
+
 pub fn run(input: &str) -> anyhow::Result<String> {
-    let (_, races) = parse_boat_races(input).expect("must parse");
-    Ok(races.iter().map(|r| r.ways()).product::<u32>().to_string())
+    Part1::solve(&Part1::parse(input)?)
+}
+
+pub fn parse(input: &str) -> Result<Vec<BoatRace>, ParseError> {
+    parse::finish(input, parse_boat_races(input))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_run() -> anyhow::Result<()> {
-        let input = "Time:      7  15   30
-Distance:  9  40  200";
-        assert_eq!("288", run(input)?);
-
-        Ok(())
-    }
+    common::example_test!(run, 1);
 
     #[test]
     fn test_parse() {
@@ -73,4 +113,32 @@ Distance:  9  40  200";
         let (_, races) = parse_boat_races(input).expect("must parse");
         assert_eq!(3, races.len(), "race length");
     }
+
+    fn ways_brute_force(race: &BoatRace) -> u64 {
+        (1..race.time).filter(|&b| race.wins(b)).count() as u64
+    }
+
+    #[test]
+    fn test_ways() {
+        for time in 0..60 {
+            for distance in 0..(time * time / 4 + 3) {
+                let race = BoatRace { distance, time };
+                assert_eq!(
+                    ways_brute_force(&race),
+                    race.ways(),
+                    "time {time} distance {distance}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_ways_perfect_square() {
+        // roots at exactly 2 and 8, which only tie the record
+        let race = BoatRace {
+            distance: 16,
+            time: 10,
+        };
+        assert_eq!(5, race.ways());
+    }
 }
@@ -1,36 +1,21 @@
+use common::{parse, ParseError, Part, Solution};
 use nom::{
     bytes::complete::tag,
     character::complete::{alpha0, digit1, line_ending, space1},
-    combinator::opt,
+    combinator::{map_res, opt},
     multi::separated_list1,
     sequence::terminated,
     IResult,
 };
 
-struct BoatRace {
-    distance: u128,
-    time: u128,
-}
-
-impl BoatRace {
-    fn ways(&self) -> u128 {
-        (1..self.time)
-            .map(|button_seconds| self.race(button_seconds))
-            .filter(|&distance| distance > self.distance)
-            .count() as u128
-    }
-
-    fn race(&self, button_seconds: u128) -> u128 {
-        let remaining = self.time - button_seconds;
-        button_seconds * remaining
-    }
-}
+use crate::part1::BoatRace;
 
-fn parse_num(input: &str) -> IResult<&str, u128> {
+fn parse_num(input: &str) -> IResult<&str, u64> {
     let (input, _) = terminated(alpha0, tag(":"))(input)?;
     let (input, _) = space1(input)?;
-    let (input, nums) = separated_list1(space1, digit1)(input)?;
-    Ok((input, nums.concat().parse().expect("should be valid int")))
+    map_res(separated_list1(space1, digit1), |nums: Vec<&str>| {
+        nums.concat().parse()
+    })(input)
 }
 
 fn parse_boat_race(input: &str) -> IResult<&str, BoatRace> {
@@ -39,28 +24,51 @@ fn parse_boat_race(input: &str) -> IResult<&str, BoatRace> {
     Ok((input, BoatRace { distance, time }))
 }
 
+pub struct Part2;
+
+impl Solution for Part2 {
+    type Input<'a> = BoatRace;
+
+    const DAY: u8 = 6;
+    const PART: Part = Part::Two;
+    const TITLE: &'static str = "Wait For It";
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse(input)?)
+    }
+
+    fn solve(race: &Self::Input<'_>) -> anyhow::Result<String> {
+        Ok(race.ways().to_string())
+    }
+}
+
 pub fn run(input: &str) -> anyhow::Result<String> {
-    let (_, race) = parse_boat_race(input).expect("must parse");
-    Ok(race.ways().to_string())
+    Part2::solve(&Part2::parse(input)?)
+}
+
+pub fn parse(input: &str) -> Result<BoatRace, ParseError> {
+    parse::finish(input, parse_boat_race(input))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    common::example_test!(run, 2);
+
     #[test]
-    fn test_run() -> anyhow::Result<()> {
+    fn test_parse() {
         let input = "Time:      7  15   30
 Distance:  9  40  200";
-        assert_eq!("71503", run(input)?);
-
-        Ok(())
+        parse_boat_race(input).expect("must parse");
     }
 
     #[test]
-    fn test_parse() {
+    fn test_parse_overflow() {
         let input = "Time:      7  15   30
-Distance:  9  40  200";
-        parse_boat_race(input).expect("must parse");
+Distance:  9  40  99999999999999999999";
+        let err = parse(input).expect_err("too large");
+        assert_eq!((2, 12), (err.line, err.column));
+        assert_eq!("a valid value", err.expected);
     }
 }
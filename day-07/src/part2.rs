@@ -1,198 +1,70 @@
-use std::{collections::HashMap, str::FromStr};
+use crate::rules::{self, camel_hand_types, HandBid, Rules};
+use common::{Part, Solution};
 
-pub fn run(input: &str) -> anyhow::Result<String> {
-    let mut hand_bids = parse(input);
-    hand_bids.sort_by(|a, b| a.hand.partial_cmp(&b.hand).unwrap());
-    Ok(hand_bids
-        .iter()
-        .enumerate()
-        .map(|(i, x)| (i + 1) as u32 * x.bid)
-        .sum::<u32>()
-        .to_string())
-}
-
-fn parse(input: &str) -> Vec<HandBids> {
-    input
-        .lines()
-        .map(|line| {
-            let (hand, bid) = line.split_once(' ').unwrap();
-            HandBids {
-                hand: Hand::from_str(hand).unwrap(),
-                bid: bid.parse::<u32>().unwrap(),
-            }
-        })
-        .collect()
-}
+pub struct Part2;
 
-#[derive(Debug)]
-struct HandBids {
-    hand: Hand,
-    bid: u32,
-}
-
-#[derive(Hash, Eq, PartialEq, PartialOrd, Debug, Clone, Copy)]
-enum Card {
-    J,
-    N(u32),
-    T,
-    Q,
-    K,
-    A,
-}
+impl Solution for Part2 {
+    type Input<'a> = Vec<HandBid>;
 
-impl TryFrom<char> for Card {
-    type Error = anyhow::Error;
+    const DAY: u8 = 7;
+    const PART: Part = Part::Two;
+    const TITLE: &'static str = "Camel Cards";
 
-    fn try_from(c: char) -> Result<Self, Self::Error> {
-        Ok(match c {
-            'A' => Self::A,
-            'K' => Self::K,
-            'Q' => Self::Q,
-            'J' => Self::J,
-            'T' => Self::T,
-            c if "23456789".contains(c) => Self::N(
-                c.to_digit(10)
-                    .ok_or_else(|| anyhow::anyhow!("invalid digit"))?,
-            ),
-            _ => anyhow::bail!("invalid card type"),
-        })
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(rules::parse(input, &joker_rules())?)
     }
-}
-
-#[derive(PartialEq, PartialOrd, Debug)]
-enum HandType {
-    HighCard,
-    OnePair,
-    TwoPair,
-    ThreeOfAKind,
-    FullHouse,
-    FourOfAKind,
-    FiveOfAKind,
-}
-
-#[derive(Eq, PartialEq, Debug)]
-struct Hand {
-    cards: Vec<Card>,
-}
-
-impl Hand {
-    fn hand_type(&self) -> HandType {
-        let mut num_jokers = 0;
-        let mut cards = HashMap::new();
-        for card in &self.cards {
-            if let Card::J = card {
-                num_jokers += 1;
-                continue;
-            }
-            cards.entry(card).and_modify(|v| *v += 1).or_insert(1u32);
-        }
-
-        // highest card
-        if let Some((card, _)) = cards.iter().max_by(|a, b| a.1.cmp(b.1)) {
-            cards.entry(card).and_modify(|v| *v += num_jokers);
-        } else {
-            // all jokers
-            cards.insert(&Card::J, 5);
-        }
 
-        let mut pairs = 0;
-        let mut triples = 0;
-        let mut quads = 0;
-        let mut pentas = 0;
-        for n in cards.values() {
-            match n {
-                2 => pairs += 1,
-                3 => triples += 1,
-                4 => quads += 1,
-                5 => pentas += 1,
-                _ => {}
-            }
-        }
-
-        match (pairs, triples, quads, pentas) {
-            (0, 0, 0, 0) => HandType::HighCard,
-            (1, 0, 0, 0) => HandType::OnePair,
-            (2, 0, 0, 0) => HandType::TwoPair,
-            (0, 1, 0, 0) => HandType::ThreeOfAKind,
-            (1, 1, 0, 0) => HandType::FullHouse,
-            (0, 0, 1, 0) => HandType::FourOfAKind,
-            (0, 0, 0, 1) => HandType::FiveOfAKind,
-            _ => unreachable!(),
-        }
+    fn solve(hand_bids: &Self::Input<'_>) -> anyhow::Result<String> {
+        Ok(joker_rules().winnings(hand_bids)?.to_string())
     }
 }
 
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        match self.hand_type().partial_cmp(&other.hand_type()) {
-            Some(std::cmp::Ordering::Equal) => {
-                for (ours, theirs) in self.cards.iter().zip(&other.cards) {
-                    if ours > theirs {
-                        return Some(std::cmp::Ordering::Greater);
-                    } else if theirs > ours {
-                        return Some(std::cmp::Ordering::Less);
-                    }
-                }
-                Some(std::cmp::Ordering::Equal)
-            }
-            comp => comp,
-        }
-    }
+pub fn run(input: &str) -> anyhow::Result<String> {
+    Part2::solve(&Part2::parse(input)?)
 }
 
-impl FromStr for Hand {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let cards = s
-            .chars()
-            .map(Card::try_from)
-            .collect::<Result<Vec<_>, Self::Err>>()?;
-        Ok(Hand { cards })
-    }
+/// Camel Cards with jokers: `J` is wild, but the weakest card in a tie.
+pub fn joker_rules() -> Rules {
+    Rules::new("J23456789TQKA", camel_hand_types()).with_wildcards("J")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rules::Hand;
+    use std::str::FromStr;
 
-    #[test]
-    fn test_run() -> anyhow::Result<()> {
-        let input = "32T3K 765
-T55J5 684
-KK677 28
-KTJJT 220
-QQQJA 483";
-        assert_eq!("5905", run(input)?);
-
-        Ok(())
-    }
+    common::example_test!(run, 2);
 
     #[test]
     fn test_card_ordering() {
-        assert!(Card::A > Card::T);
-        assert!(Card::T > Card::N(9));
-        assert!(Card::N(5) > Card::N(2));
+        let rules = joker_rules();
+        assert!(rules.rank('A') > rules.rank('T'));
+        assert!(rules.rank('T') > rules.rank('9'));
+        assert!(rules.rank('5') > rules.rank('2'));
+        assert!(rules.rank('2') > rules.rank('J'));
     }
 
     #[test]
-    fn test_hand_ordering() {
-        let hand1 = Hand::from_str("33332").expect("parse");
-        let hand2 = Hand::from_str("2AAAA").expect("parse");
-        assert!(hand1 > hand2);
-
-        let hand1 = Hand::from_str("77888").expect("parse");
-        let hand2 = Hand::from_str("77788").expect("parse");
-        assert!(hand1 > hand2);
+    fn test_hand_ordering() -> anyhow::Result<()> {
+        let rules = joker_rules();
+        let strength = |s| rules.strength(&Hand::from_str(s).expect("parse"));
+        assert!(strength("33332")? > strength("2AAAA")?);
+        assert!(strength("77888")? > strength("77788")?);
+        assert!(strength("AJAAA")? > strength("AAA4A")?);
+        assert!(strength("JJJJJ")? < strength("22222")?);
 
-        let hand1 = Hand::from_str("AAA4A").expect("parse");
-        let hand2 = Hand::from_str("AJAAA").expect("parse");
-        assert!(hand2 > hand1);
+        Ok(())
     }
 
     #[test]
-    fn test_hand_type() {
+    fn test_hand_type() -> anyhow::Result<()> {
+        let rules = joker_rules();
         let hand = Hand::from_str("AAAA4").expect("parse");
-        assert_eq!(HandType::FourOfAKind, hand.hand_type());
+        assert_eq!("FourOfAKind", rules.hand_type(&hand)?.name);
+        let hand = Hand::from_str("QJJQ2").expect("parse");
+        assert_eq!("FourOfAKind", rules.hand_type(&hand)?.name);
+
+        Ok(())
     }
 }
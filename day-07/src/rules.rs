@@ -0,0 +1,343 @@
+use common::ParseError;
+use std::{collections::HashMap, str::FromStr};
+
+/// How hands of the same type are ordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// compare card by card in the order they were dealt, like Camel Cards
+    Positional,
+    /// compare the largest groups first and then the kickers, like poker
+    Grouped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandType {
+    pub name: &'static str,
+    /// sizes of the groups of matching cards, largest first
+    pub groups: Vec<usize>,
+    /// whether the cards must also run in sequence, like a poker straight
+    pub straight: bool,
+}
+
+impl HandType {
+    pub fn new(name: &'static str, groups: &[usize]) -> Self {
+        HandType {
+            name,
+            groups: groups.to_vec(),
+            straight: false,
+        }
+    }
+
+    /// Five cards of consecutive ranks. An ace may also be low, below the
+    /// two, as in A-2-3-4-5.
+    pub fn straight(name: &'static str) -> Self {
+        HandType {
+            name,
+            groups: vec![1; 5],
+            straight: true,
+        }
+    }
+}
+
+/// The hand types of Camel Cards, weakest first.
+pub fn camel_hand_types() -> Vec<HandType> {
+    vec![
+        HandType::new("HighCard", &[1, 1, 1, 1, 1]),
+        HandType::new("OnePair", &[2, 1, 1, 1]),
+        HandType::new("TwoPair", &[2, 2, 1]),
+        HandType::new("ThreeOfAKind", &[3, 1, 1]),
+        HandType::new("FullHouse", &[3, 2]),
+        HandType::new("FourOfAKind", &[4, 1]),
+        HandType::new("FiveOfAKind", &[5]),
+    ]
+}
+
+/// The hand types of poker without suits, weakest first. There are no
+/// flushes without suits, and no five of a kind without wildcards.
+pub fn poker_hand_types() -> Vec<HandType> {
+    vec![
+        HandType::new("HighCard", &[1, 1, 1, 1, 1]),
+        HandType::new("OnePair", &[2, 1, 1, 1]),
+        HandType::new("TwoPair", &[2, 2, 1]),
+        HandType::new("ThreeOfAKind", &[3, 1, 1]),
+        HandType::straight("Straight"),
+        HandType::new("FullHouse", &[3, 2]),
+        HandType::new("FourOfAKind", &[4, 1]),
+    ]
+}
+
+/// Everything that decides how hands are ranked against each other.
+#[derive(Debug, Clone)]
+pub struct Rules {
+    /// every valid card, weakest first
+    order: Vec<char>,
+    /// cards that count as whichever card makes the best hand
+    wildcards: Vec<char>,
+    /// every possible hand type, weakest first
+    hand_types: Vec<HandType>,
+    tie_break: TieBreak,
+}
+
+impl Rules {
+    pub fn new(order: &str, hand_types: Vec<HandType>) -> Self {
+        Rules {
+            order: order.chars().collect(),
+            wildcards: vec![],
+            hand_types,
+            tie_break: TieBreak::Positional,
+        }
+    }
+
+    pub fn with_wildcards(mut self, wildcards: &str) -> Self {
+        self.wildcards = wildcards.chars().collect();
+        self
+    }
+
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /// Poker without suits: aces high, and ties settled by the highest
+    /// group before the kickers.
+    pub fn poker() -> Self {
+        Rules::new("23456789TJQKA", poker_hand_types()).with_tie_break(TieBreak::Grouped)
+    }
+
+    pub fn rank(&self, card: char) -> Option<usize> {
+        self.order.iter().position(|&c| c == card)
+    }
+
+    fn is_wild(&self, card: char) -> bool {
+        self.wildcards.contains(&card)
+    }
+
+    /// Check that every card in `hand` exists under these rules.
+    pub fn validate(&self, hand: &Hand) -> anyhow::Result<()> {
+        match hand.cards.iter().find(|&&c| self.rank(c).is_none()) {
+            Some(card) => anyhow::bail!("invalid card type {}", card),
+            None => Ok(()),
+        }
+    }
+
+    /// Non-wild cards grouped by kind, largest group first and then highest
+    /// rank first, with every wildcard added to the first group.
+    fn groups(&self, hand: &Hand) -> Vec<(usize, usize)> {
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+        let mut wild = 0;
+        for &card in &hand.cards {
+            if self.is_wild(card) {
+                wild += 1;
+                continue;
+            }
+            if let Some(rank) = self.rank(card) {
+                *counts.entry(rank).or_default() += 1;
+            }
+        }
+
+        let mut groups: Vec<(usize, usize)> = counts
+            .into_iter()
+            .map(|(rank, count)| (count, rank))
+            .collect();
+        groups.sort_by(|a, b| b.cmp(a));
+        match groups.first_mut() {
+            Some((count, _)) => *count += wild,
+            // nothing but wildcards, so they can be the best card there is
+            None if wild > 0 => groups.push((wild, self.order.len().saturating_sub(1))),
+            None => {}
+        }
+        groups
+    }
+
+    /// The rank of the highest card if `groups` is five cards in sequence,
+    /// counting the ace as low in A-2-3-4-5.
+    fn straight_high(&self, groups: &[(usize, usize)]) -> Option<usize> {
+        if groups.len() != 5 || groups.iter().any(|&(count, _)| count != 1) {
+            return None;
+        }
+        // single cards, so these are sorted highest first
+        let ranks: Vec<usize> = groups.iter().map(|&(_, rank)| rank).collect();
+        let ace = self.order.len() - 1;
+        if ranks[0] - ranks[4] == 4 {
+            Some(ranks[0])
+        } else if ranks == [ace, 3, 2, 1, 0] {
+            Some(ranks[1])
+        } else {
+            None
+        }
+    }
+
+    /// The position in `hand_types` of the strongest type `groups` makes.
+    fn type_index(&self, groups: &[(usize, usize)]) -> anyhow::Result<usize> {
+        let sizes: Vec<usize> = groups.iter().map(|&(count, _)| count).collect();
+        let straight = self.straight_high(groups).is_some();
+        self.hand_types
+            .iter()
+            .rposition(|t| t.groups == sizes && (straight || !t.straight))
+            .ok_or_else(|| anyhow::anyhow!("no hand type for groups {:?}", sizes))
+    }
+
+    pub fn hand_type(&self, hand: &Hand) -> anyhow::Result<&HandType> {
+        let index = self.type_index(&self.groups(hand))?;
+        Ok(&self.hand_types[index])
+    }
+
+    /// A key that sorts hands from weakest to strongest.
+    pub fn strength(&self, hand: &Hand) -> anyhow::Result<Strength> {
+        self.validate(hand)?;
+        let groups = self.groups(hand);
+        let hand_type = self.type_index(&groups)?;
+        let tie_break = match self.tie_break {
+            TieBreak::Positional => hand
+                .cards
+                .iter()
+                .map(|&c| {
+                    // wildcards are as weak as possible once the type is set
+                    if self.is_wild(c) {
+                        return 0;
+                    }
+                    self.rank(c).map_or(0, |rank| rank + 1)
+                })
+                .collect(),
+            // a straight is only as high as its top card, which an ace
+            // low straight doesn't have
+            TieBreak::Grouped if self.hand_types[hand_type].straight => {
+                self.straight_high(&groups).into_iter().collect()
+            }
+            TieBreak::Grouped => groups.iter().map(|&(_, rank)| rank).collect(),
+        };
+        Ok(Strength {
+            hand_type,
+            tie_break,
+        })
+    }
+
+    /// Total winnings: each bid multiplied by the rank of its hand.
+    pub fn winnings(&self, hand_bids: &[HandBid]) -> anyhow::Result<u64> {
+        let mut ranked = hand_bids
+            .iter()
+            .map(|hb| Ok((self.strength(&hb.hand)?, hb.bid)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        ranked.sort();
+        Ok(ranked
+            .iter()
+            .enumerate()
+            .map(|(i, (_, bid))| (i + 1) as u64 * *bid as u64)
+            .sum())
+    }
+}
+
+/// Sorts hands from weakest to strongest under one set of [`Rules`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Strength {
+    hand_type: usize,
+    tie_break: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hand {
+    cards: Vec<char>,
+}
+
+impl FromStr for Hand {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            anyhow::bail!("empty hand");
+        }
+        Ok(Hand {
+            cards: s.chars().collect(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct HandBid {
+    pub hand: Hand,
+    pub bid: u32,
+}
+
+/// Parse one hand and bid per line, rejecting cards `rules` doesn't know.
+pub fn parse(input: &str, rules: &Rules) -> Result<Vec<HandBid>, ParseError> {
+    input
+        .lines()
+        .map(|line| {
+            let (hand, bid) = line
+                .split_once(' ')
+                .ok_or_else(|| ParseError::within(input, line, "`<hand> <bid>`"))?;
+            if hand.is_empty() {
+                return Err(ParseError::within(input, hand, "a hand of cards"));
+            }
+            if let Some((i, _)) = hand.char_indices().find(|&(_, c)| rules.rank(c).is_none()) {
+                let cards: String = rules.order.iter().collect();
+                return Err(ParseError::within(
+                    input,
+                    &hand[i..],
+                    format!("one of the cards `{cards}`"),
+                ));
+            }
+            Ok(HandBid {
+                hand: Hand {
+                    cards: hand.chars().collect(),
+                },
+                bid: bid
+                    .parse::<u32>()
+                    .map_err(|_| ParseError::within(input, bid, "a bid"))?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hand(s: &str) -> Hand {
+        Hand::from_str(s).expect("parse")
+    }
+
+    #[test]
+    fn test_multiple_wildcards() -> anyhow::Result<()> {
+        let rules = Rules::new("JQ23456789TKA", camel_hand_types()).with_wildcards("JQ");
+        assert_eq!("FiveOfAKind", rules.hand_type(&hand("JQ222"))?.name);
+        assert_eq!("FourOfAKind", rules.hand_type(&hand("J23Q3"))?.name);
+        assert_eq!("FiveOfAKind", rules.hand_type(&hand("JJQQJ"))?.name);
+        assert!(rules.strength(&hand("2JQQQ"))? > rules.strength(&hand("QQQQ2"))?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_poker() -> anyhow::Result<()> {
+        let rules = Rules::poker();
+        // the pair decides before the kickers do
+        assert!(rules.strength(&hand("33AK2"))? > rules.strength(&hand("A22KQ"))?);
+        // full houses compare the triple first
+        assert!(rules.strength(&hand("22333"))? > rules.strength(&hand("22233"))?);
+        assert!(rules.strength(&hand("KKK22"))? > rules.strength(&hand("33322"))?);
+        assert!(rules.strength(&hand("AK942"))? > rules.strength(&hand("AK932"))?);
+
+        // straights, with the ace low in the wheel
+        assert_eq!("Straight", rules.hand_type(&hand("65432"))?.name);
+        assert_eq!("Straight", rules.hand_type(&hand("A2345"))?.name);
+        assert_eq!("HighCard", rules.hand_type(&hand("KA234"))?.name);
+        assert!(rules.strength(&hand("23456"))? > rules.strength(&hand("AAA23"))?);
+        assert!(rules.strength(&hand("33322"))? > rules.strength(&hand("TJQKA"))?);
+        assert!(rules.strength(&hand("23456"))? > rules.strength(&hand("A2345"))?);
+        assert!(rules.strength(&hand("TJQKA"))? > rules.strength(&hand("9TJQK"))?);
+        assert!(rules.hand_type(&hand("22222")).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_card() {
+        let rules = Rules::poker();
+        assert!(rules.strength(&hand("1AAAA")).is_err());
+        let err = parse("23456 10\n2345X 10", &rules).expect_err("unknown card");
+        assert_eq!((2, 5), (err.line, err.column));
+        let err = parse("23456 ten", &rules).expect_err("bad bid");
+        assert_eq!((1, 7), (err.line, err.column));
+    }
+}
@@ -1,40 +1,70 @@
-use std::collections::HashMap;
+use common::{parse, ParseError, Part, Solution};
+use std::collections::{HashMap, HashSet};
 
 use nom::{
     bytes::complete::tag,
     character::complete::{self, alpha1, line_ending, one_of},
+    combinator::map_res,
     multi::{many1, separated_list1},
     sequence::{delimited, separated_pair, terminated},
     IResult,
 };
 
+pub struct Part1;
+
+impl Solution for Part1 {
+    type Input<'a> = State<'a>;
+
+    const DAY: u8 = 8;
+    const PART: Part = Part::One;
+    const TITLE: &'static str = "Haunted Wasteland";
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse(input)?)
+    }
+
+    fn solve(state: &Self::Input<'_>) -> anyhow::Result<String> {
+        Ok(state.steps("AAA", "ZZZ")?.to_string())
+    }
+}
+
 pub fn run(input: &str) -> anyhow::Result<String> {
-    let (_, state) = parse(input).expect("parse");
-    Ok(state.steps("AAA", "ZZZ").to_string())
+    Part1::solve(&Part1::parse(input)?)
+}
+
+pub fn parse(input: &str) -> Result<State<'_>, ParseError> {
+    parse::finish(input, parse_state(input))
 }
 
-struct State<'a> {
+pub struct State<'a> {
     directions: Vec<Direction>,
     map: HashMap<&'a str, (&'a str, &'a str)>,
 }
 
 impl<'a> State<'a> {
-    fn steps(&self, start: &str, end: &str) -> usize {
-        let mut direction = self.directions.iter().cycle();
+    /// The number of steps from `start` to `end`, taking at least one.
+    fn steps(&self, start: &str, end: &str) -> anyhow::Result<usize> {
+        let mut seen: HashSet<(&str, usize)> = HashSet::new();
         let mut curr = start;
         let mut steps = 0;
         loop {
-            let (left, right) = self.map.get(curr).unwrap();
-            match direction.next().unwrap() {
+            let index = steps % self.directions.len();
+            if !seen.insert((curr, index)) {
+                anyhow::bail!("{} can't be reached from {}", end, start);
+            }
+            let (left, right) = self
+                .map
+                .get(curr)
+                .ok_or_else(|| anyhow::anyhow!("no mapping for node {}", curr))?;
+            match self.directions[index] {
                 Direction::R => curr = right,
                 Direction::L => curr = left,
             }
             steps += 1;
             if curr == end {
-                break;
+                return Ok(steps);
             }
         }
-        steps
     }
 }
 
@@ -56,15 +86,10 @@ impl TryFrom<char> for Direction {
 }
 
 fn parse_directions(input: &str) -> IResult<&str, Vec<Direction>> {
-    terminated(many1(one_of("RL")), line_ending)(input).map(|(input, chars)| {
-        (
-            input,
-            chars
-                .iter()
-                .map(|&c| Direction::try_from(c).expect("valid direction"))
-                .collect(),
-        )
-    })
+    terminated(
+        many1(map_res(one_of("RL"), Direction::try_from)),
+        line_ending,
+    )(input)
 }
 
 fn parse_mapping(input: &str) -> IResult<&str, (&str, (&str, &str))> {
@@ -86,7 +111,7 @@ fn parse_map(input: &str) -> IResult<&str, HashMap<&str, (&str, &str)>> {
     Ok((input, m))
 }
 
-fn parse(input: &str) -> IResult<&str, State> {
+fn parse_state(input: &str) -> IResult<&str, State<'_>> {
     let (input, directions) = parse_directions(input)?;
     let (input, _) = line_ending(input)?;
     let (input, map) = parse_map(input)?;
@@ -97,14 +122,16 @@ fn parse(input: &str) -> IResult<&str, State> {
 mod tests {
     use super::*;
 
+    common::example_test!(run, 1);
+
     #[test]
-    fn test_run() -> anyhow::Result<()> {
-        let input = "LLR
+    fn test_unreachable() -> anyhow::Result<()> {
+        let state = parse("L\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)")?;
+        assert!(state.steps("AAA", "ZZZ").is_err());
+        assert_eq!(2, state.steps("AAA", "AAA")?);
 
-AAA = (BBB, BBB)
-BBB = (AAA, ZZZ)
-ZZZ = (ZZZ, ZZZ)";
-        assert_eq!("6", run(input)?);
+        let state = parse("L\n\nAAA = (BBB, BBB)")?;
+        assert!(state.steps("AAA", "ZZZ").is_err());
 
         Ok(())
     }
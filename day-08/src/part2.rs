@@ -1,65 +1,171 @@
+use common::{
+    math::{self, MathError},
+    parse, ParseError, Part, Solution,
+};
 use std::collections::HashMap;
 
 use nom::{
     bytes::complete::tag,
     character::complete::{self, alphanumeric1, line_ending, one_of},
+    combinator::map_res,
     multi::{many1, separated_list1},
     sequence::{delimited, separated_pair, terminated},
     IResult,
 };
 
+pub struct Part2;
+
+impl Solution for Part2 {
+    type Input<'a> = State<'a>;
+
+    const DAY: u8 = 8;
+    const PART: Part = Part::Two;
+    const TITLE: &'static str = "Haunted Wasteland";
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse(input)?)
+    }
+
+    fn solve(state: &Self::Input<'_>) -> anyhow::Result<String> {
+        Ok(state.steps()?.to_string())
+    }
+}
+
 pub fn run(input: &str) -> anyhow::Result<String> {
-    let (_, state) = parse(input).expect("parse");
-    Ok(state.steps().to_string())
+    Part2::solve(&Part2::parse(input)?)
 }
 
-struct State<'a> {
+pub fn parse(input: &str) -> Result<State<'_>, ParseError> {
+    parse::finish(input, parse_state(input))
+}
+
+pub struct State<'a> {
     directions: Vec<Direction>,
     map: HashMap<&'a str, (&'a str, &'a str)>,
 }
 
 impl<'a> State<'a> {
-    fn steps(&self) -> usize {
-        let nodes = self.map.keys().map(|s| *s).filter(|s| s.ends_with('A'));
-        let cycle_lengths: Vec<_> = nodes.map(|node| self.steps_single(node)).collect();
-        lcm(&cycle_lengths)
+    fn steps(&self) -> anyhow::Result<u64> {
+        let cycles = self
+            .map
+            .keys()
+            .copied()
+            .filter(|s| s.ends_with('A'))
+            .map(|node| self.analyze(node))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        first_common_hit(&cycles)?
+            .ok_or_else(|| anyhow::anyhow!("the ghosts never all stand on a Z node at once"))
     }
 
-    fn steps_single(&self, start: &str) -> usize {
-        let mut direction = self.directions.iter().cycle();
+    /// Walk a single ghost from `start` until its (node, direction index)
+    /// state repeats, noting every step that lands on a Z node on the way.
+    pub fn analyze(&self, start: &'a str) -> anyhow::Result<Cycle> {
+        let mut seen: HashMap<(&str, usize), usize> = HashMap::new();
+        let mut hits = vec![];
         let mut curr = start;
-        let mut steps = 0;
+        let mut step = 0;
         loop {
-            let (left, right) = self.map.get(curr).unwrap();
-            match direction.next().unwrap() {
-                Direction::R => curr = right,
-                Direction::L => curr = left,
+            let index = step % self.directions.len();
+            if let Some(&prefix) = seen.get(&(curr, index)) {
+                let (prefix_hits, cycle_hits): (Vec<usize>, Vec<usize>) =
+                    hits.into_iter().partition(|&hit| hit < prefix);
+                return Ok(Cycle {
+                    prefix,
+                    length: step - prefix,
+                    prefix_hits,
+                    cycle_hits: cycle_hits.into_iter().map(|hit| hit - prefix).collect(),
+                });
             }
-            steps += 1;
+            seen.insert((curr, index), step);
+
             if curr.ends_with('Z') {
-                break;
+                hits.push(step);
+            }
+            let (left, right) = self
+                .map
+                .get(curr)
+                .ok_or_else(|| anyhow::anyhow!("no mapping for node {}", curr))?;
+            match self.directions[index] {
+                Direction::R => curr = right,
+                Direction::L => curr = left,
             }
+            step += 1;
         }
-        steps
     }
 }
 
-// https://github.com/TheAlgorithms/Rust/blob/master/src/math/lcm_of_n_numbers.rs
-pub fn lcm(nums: &[usize]) -> usize {
-    if nums.len() == 1 {
-        return nums[0];
+/// The path of a single ghost, which always ends up looping.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// steps taken before the loop is entered
+    pub prefix: usize,
+    /// steps around the loop
+    pub length: usize,
+    /// steps before the loop that land on a Z node, in order
+    pub prefix_hits: Vec<usize>,
+    /// offsets from the start of the loop that land on a Z node, in order
+    pub cycle_hits: Vec<usize>,
+}
+
+impl Cycle {
+    pub fn hits_at(&self, step: usize) -> bool {
+        if step < self.prefix {
+            return self.prefix_hits.contains(&step);
+        }
+        self.cycle_hits
+            .contains(&((step - self.prefix) % self.length))
     }
-    let a = nums[0];
-    let b = lcm(&nums[1..]);
-    a * b / gcd(a, b)
 }
 
-// https://github.com/TheAlgorithms/Rust/blob/master/src/math/lcm_of_n_numbers.rs
-fn gcd(a: usize, b: usize) -> usize {
-    if b == 0 {
-        return a;
+/// The first step at which every ghost is on a Z node, if there is one.
+fn first_common_hit(cycles: &[Cycle]) -> Result<Option<u64>, MathError> {
+    let Some(latest) = cycles.iter().max_by_key(|c| c.prefix) else {
+        return Ok(None);
+    };
+
+    // before every ghost is looping, a common hit has to be one of the prefix
+    // hits of the ghost that takes the longest to start looping
+    if let Some(&step) = latest
+        .prefix_hits
+        .iter()
+        .find(|&&step| cycles.iter().all(|c| c.hits_at(step)))
+    {
+        return Ok(Some(step as u64));
+    }
+
+    // after that, each ghost hits on a few residues modulo its loop length,
+    // so combine every choice of residue into a single congruence
+    let mut congruences: Vec<(i128, i128)> = vec![(0, 1)];
+    for cycle in cycles {
+        let length = cycle.length as i128;
+        let mut merged = vec![];
+        for &congruence in &congruences {
+            for &offset in &cycle.cycle_hits {
+                let residue = (cycle.prefix + offset) as i128;
+                match math::crt([congruence, (residue, length)]) {
+                    Ok(congruence) => merged.push(congruence),
+                    Err(MathError::NoSolution) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        congruences = merged;
+        congruences.sort();
+        congruences.dedup();
     }
-    gcd(b, a % b)
+
+    let start = latest.prefix as i128;
+    Ok(congruences
+        .into_iter()
+        .map(|(residue, modulus)| {
+            if residue >= start {
+                residue
+            } else {
+                residue + (start - residue + modulus - 1) / modulus * modulus
+            }
+        })
+        .min()
+        .map(|step| step as u64))
 }
 
 enum Direction {
@@ -80,15 +186,10 @@ impl TryFrom<char> for Direction {
 }
 
 fn parse_directions(input: &str) -> IResult<&str, Vec<Direction>> {
-    terminated(many1(one_of("RL")), line_ending)(input).map(|(input, chars)| {
-        (
-            input,
-            chars
-                .iter()
-                .map(|&c| Direction::try_from(c).expect("valid direction"))
-                .collect(),
-        )
-    })
+    terminated(
+        many1(map_res(one_of("RL"), Direction::try_from)),
+        line_ending,
+    )(input)
 }
 
 fn parse_mapping(input: &str) -> IResult<&str, (&str, (&str, &str))> {
@@ -110,7 +211,7 @@ fn parse_map(input: &str) -> IResult<&str, HashMap<&str, (&str, &str)>> {
     Ok((input, m))
 }
 
-fn parse(input: &str) -> IResult<&str, State> {
+fn parse_state(input: &str) -> IResult<&str, State<'_>> {
     let (input, directions) = parse_directions(input)?;
     let (input, _) = line_ending(input)?;
     let (input, map) = parse_map(input)?;
@@ -121,19 +222,76 @@ fn parse(input: &str) -> IResult<&str, State> {
 mod tests {
     use super::*;
 
+    common::example_test!(run, 2);
+
+    /// Step every ghost at once until they all stand on a Z node.
+    fn brute_force(state: &State, limit: usize) -> Option<usize> {
+        let mut ghosts: Vec<&str> = state
+            .map
+            .keys()
+            .copied()
+            .filter(|s| s.ends_with('A'))
+            .collect();
+        for (step, direction) in state.directions.iter().cycle().enumerate().take(limit) {
+            if step > 0 && ghosts.iter().all(|g| g.ends_with('Z')) {
+                return Some(step);
+            }
+            for ghost in ghosts.iter_mut() {
+                let (left, right) = state.map[ghost];
+                *ghost = match direction {
+                    Direction::R => right,
+                    Direction::L => left,
+                };
+            }
+        }
+        None
+    }
+
     #[test]
-    fn test_run() -> anyhow::Result<()> {
+    fn test_offset_cycles() -> anyhow::Result<()> {
+        // 11A is on a Z node every other step, 22A lands on one after a single
+        // step and then every third step, which a plain lcm gets wrong
         let input = "LR
 
 11A = (11B, XXX)
 11B = (XXX, 11Z)
 11Z = (11B, XXX)
-22A = (22B, XXX)
+22A = (22Z, 22Z)
+22Z = (22B, 22B)
 22B = (22C, 22C)
 22C = (22Z, 22Z)
+XXX = (XXX, XXX)";
+        let state = parse(input)?;
+        assert_eq!(
+            Cycle {
+                prefix: 1,
+                length: 6,
+                prefix_hits: vec![],
+                cycle_hits: vec![0, 3],
+            },
+            state.analyze("22A")?
+        );
+        assert_eq!(Some(4), brute_force(&state, 100));
+        assert_eq!("4", run(input)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_common_hit() -> anyhow::Result<()> {
+        // 22A only ever reaches a Z node on its first step
+        let input = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22Z, 22Z)
 22Z = (22B, 22B)
+22B = (22B, 22B)
 XXX = (XXX, XXX)";
-        assert_eq!("6", run(input)?);
+        let state = parse(input)?;
+        assert_eq!(None, brute_force(&state, 100));
+        assert!(run(input).is_err());
 
         Ok(())
     }
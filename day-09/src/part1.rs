@@ -1,68 +1,178 @@
+use common::{parse, ParseError, Part, Solution};
 use itertools::Itertools;
 use nom::{
-    character::complete::{self, line_ending, space1},
+    character::complete::{self, space1},
     multi::separated_list1,
     IResult,
 };
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::Zero;
+
+pub struct Part1;
+
+impl Solution for Part1 {
+    type Input<'a> = Vec<History>;
+
+    const DAY: u8 = 9;
+    const PART: Part = Part::One;
+    const TITLE: &'static str = "Mirage Maintenance";
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse(input)?)
+    }
+
+    fn solve(histories: &Self::Input<'_>) -> anyhow::Result<String> {
+        Ok(histories
+            .iter()
+            .map(|h| h.forward(1))
+            .sum::<i64>()
+            .to_string())
+    }
+}
 
 pub fn run(input: &str) -> anyhow::Result<String> {
-    let (_, histories) = parse(input).expect("parse");
-    Ok(histories
-        .iter()
-        .map(|h| h.find_next())
-        .sum::<i64>()
-        .to_string())
+    Part1::solve(&Part1::parse(input)?)
+}
+
+pub fn parse(input: &str) -> Result<Vec<History>, ParseError> {
+    input
+        .lines()
+        .map(|line| parse::finish(input, parse_history(line)))
+        .collect()
 }
 
 #[derive(Debug)]
-struct History(Vec<i64>);
+pub struct History {
+    /// the history itself, even if it's all zeros, followed by each row of
+    /// differences down to, but not including, the first row of all zeros
+    table: Vec<Vec<i64>>,
+}
 
 impl History {
-    fn find_next(&self) -> i64 {
-        let mut ends = vec![];
-        let mut nums = self.0.clone();
+    pub fn new(values: Vec<i64>) -> Self {
+        let mut table = vec![values];
         loop {
-            if nums.iter().all(|&x| x == 0) {
-                break;
-            }
-            nums = nums
+            let next: Vec<i64> = table[table.len() - 1]
                 .iter()
                 .tuple_windows()
-                .with_position()
-                .map(|(position, (a, b))| {
-                    match position {
-                        itertools::Position::Last | itertools::Position::Only => ends.push(*b),
-                        _ => {}
-                    };
-                    b - a
-                })
+                .map(|(a, b)| b - a)
                 .collect();
+            if next.iter().all(|&x| x == 0) {
+                break;
+            }
+            table.push(next);
         }
-        ends.iter().sum()
+        History { table }
+    }
+
+    pub fn values(&self) -> &[i64] {
+        &self.table[0]
+    }
+
+    pub fn differences(&self) -> &[Vec<i64>] {
+        &self.table
+    }
+
+    /// The value `steps` places after the last one.
+    pub fn forward(&self, steps: usize) -> i64 {
+        let mut ends: Vec<i64> = self
+            .table
+            .iter()
+            .map(|row| row.last().copied().unwrap_or(0))
+            .collect();
+        for _ in 0..steps {
+            // each row grows by the new value of the row beneath it
+            for i in (0..ends.len().saturating_sub(1)).rev() {
+                ends[i] += ends[i + 1];
+            }
+        }
+        ends.first().copied().unwrap_or(0)
+    }
+
+    /// The value `steps` places before the first one.
+    pub fn backward(&self, steps: usize) -> i64 {
+        let mut starts: Vec<i64> = self
+            .table
+            .iter()
+            .map(|row| row.first().copied().unwrap_or(0))
+            .collect();
+        for _ in 0..steps {
+            for i in (0..starts.len().saturating_sub(1)).rev() {
+                starts[i] -= starts[i + 1];
+            }
+        }
+        starts.first().copied().unwrap_or(0)
+    }
+
+    /// Evaluate the polynomial through the history at any position, where
+    /// the values sit at `0, 1, 2, ...`. Uses the Lagrange form directly on
+    /// the values, so `x` can be fractional and nothing is rounded.
+    pub fn evaluate(&self, x: &BigRational) -> BigRational {
+        let values = self.values();
+        let mut sum = BigRational::zero();
+        for (i, &y) in values.iter().enumerate() {
+            let mut term = BigRational::from_integer(BigInt::from(y));
+            for j in (0..values.len()).filter(|&j| j != i) {
+                let (i, j) = (BigInt::from(i), BigInt::from(j));
+                term *=
+                    (x - BigRational::from_integer(j.clone())) / BigRational::from_integer(i - j);
+            }
+            sum += term;
+        }
+        sum
     }
 }
 
 fn parse_history(input: &str) -> IResult<&str, History> {
     let (input, list) = separated_list1(space1, complete::i64)(input)?;
-    Ok((input, History(list)))
-}
-
-fn parse(input: &str) -> IResult<&str, Vec<History>> {
-    let (input, histories) = separated_list1(line_ending, parse_history)(input)?;
-    Ok((input, histories))
+    Ok((input, History::new(list)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    common::example_test!(run, 1);
+
+    #[test]
+    fn test_extrapolate() {
+        let history = History::new(vec![10, 13, 16, 21, 30, 45]);
+        assert_eq!(4, history.differences().len());
+        assert_eq!(68, history.forward(1));
+        assert_eq!(5, history.backward(1));
+
+        // 2x^2 - 3x + 1 sampled at x = 0..4
+        let history = History::new(vec![1, 0, 3, 10]);
+        assert_eq!(21, history.forward(1));
+        assert_eq!(36, history.forward(2));
+        assert_eq!(6, history.backward(1));
+        assert_eq!(15, history.backward(2));
+        assert_eq!(10, history.forward(0));
+    }
+
     #[test]
-    fn test_run() -> anyhow::Result<()> {
-        let input = "0 3 6 9 12 15
-1 3 6 10 15 21
-10 13 16 21 30 45";
-        assert_eq!("114", run(input)?);
+    fn test_all_zeros() {
+        let history = History::new(vec![0, 0, 0]);
+        assert_eq!(&[0, 0, 0], history.values());
+        assert_eq!(1, history.differences().len());
+        assert_eq!(0, history.forward(1));
+        assert_eq!(0, history.backward(1));
+    }
 
-        Ok(())
+    #[test]
+    fn test_evaluate() {
+        let history = History::new(vec![1, 0, 3, 10]);
+        let at = |n: i64, d: i64| history.evaluate(&BigRational::new(n.into(), d.into()));
+        for steps in 0..5 {
+            let forward = BigRational::from_integer(history.forward(steps).into());
+            assert_eq!(forward, at(3 + steps as i64, 1));
+            let backward = BigRational::from_integer(history.backward(steps).into());
+            assert_eq!(backward, at(-(steps as i64), 1));
+        }
+        // 2(1/2)^2 - 3(1/2) + 1 = 0
+        assert_eq!(BigRational::zero(), at(1, 2));
+        // 2(1/3)^2 - 3(1/3) + 1 = 2/9
+        assert_eq!(BigRational::new(2.into(), 9.into()), at(1, 3));
     }
 }
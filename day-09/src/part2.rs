@@ -1,68 +1,36 @@
-use itertools::Itertools;
-use nom::{
-    character::complete::{self, line_ending, space1},
-    multi::separated_list1,
-    IResult,
-};
+use common::{Part, Solution};
 
-pub fn run(input: &str) -> anyhow::Result<String> {
-    let (_, histories) = parse(input).expect("parse");
-    Ok(histories
-        .iter()
-        .map(|h| h.find_next())
-        .sum::<i64>()
-        .to_string())
-}
+use crate::part1::History;
+
+pub struct Part2;
 
-#[derive(Debug)]
-struct History(Vec<i64>);
+impl Solution for Part2 {
+    type Input<'a> = Vec<History>;
 
-impl History {
-    fn find_next(&self) -> i64 {
-        let mut ends = vec![];
-        let mut nums = self.0.clone();
-        loop {
-            if nums.iter().all(|&x| x == 0) {
-                break;
-            }
-            nums = nums
-                .iter()
-                .tuple_windows()
-                .with_position()
-                .map(|(position, (a, b))| {
-                    match position {
-                        itertools::Position::First | itertools::Position::Only => ends.push(*a),
-                        _ => {}
-                    };
-                    a - b
-                })
-                .collect();
-        }
-        ends.iter().sum()
+    const DAY: u8 = 9;
+    const PART: Part = Part::Two;
+    const TITLE: &'static str = "Mirage Maintenance";
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(crate::part1::parse(input)?)
     }
-}
 
-fn parse_history(input: &str) -> IResult<&str, History> {
-    let (input, list) = separated_list1(space1, complete::i64)(input)?;
-    Ok((input, History(list)))
+    fn solve(histories: &Self::Input<'_>) -> anyhow::Result<String> {
+        Ok(histories
+            .iter()
+            .map(|h| h.backward(1))
+            .sum::<i64>()
+            .to_string())
+    }
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<History>> {
-    let (input, histories) = separated_list1(line_ending, parse_history)(input)?;
-    Ok((input, histories))
+pub fn run(input: &str) -> anyhow::Result<String> {
+    Part2::solve(&Part2::parse(input)?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_run() -> anyhow::Result<()> {
-        let input = "0 3 6 9 12 15
-1 3 6 10 15 21
-10 13 16 21 30 45";
-        assert_eq!("2", run(input)?);
-
-        Ok(())
-    }
+    common::example_test!(run, 2);
 }
@@ -0,0 +1,431 @@
+//! A stand-in for adventofcode.com that serves puzzle inputs, puzzle pages
+//! and answer submissions from fixture files, so the fetch and submit flow
+//! can run without the real site.
+//!
+//! Fixtures live under `{fixtures}/{year}/{day:02}/`:
+//!
+//! - `input.txt`: the puzzle input
+//! - `puzzle.html`: the puzzle page
+//! - `answer{part}.txt`: the accepted answer for a part
+
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, BufRead, BufReader},
+    net::{SocketAddr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    /// Read one HTTP/1.1 request.
+    pub fn read(reader: &mut impl BufRead) -> io::Result<Self> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+            return Err(invalid("malformed request line"));
+        };
+        let (method, path) = (method.to_string(), path.to_string());
+
+        let mut headers = vec![];
+        loop {
+            line.clear();
+            reader.read_line(&mut line)?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let (name, value) = line
+                .split_once(':')
+                .ok_or_else(|| invalid("malformed header"))?;
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+
+        let length = headers
+            .iter()
+            .find(|(name, _)| name == "content-length")
+            .map_or(Ok(0), |(_, value)| value.parse::<usize>())
+            .map_err(|_| invalid("malformed content length"))?;
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+
+        Ok(Request {
+            method,
+            path,
+            headers,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        })
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        self.header("cookie")?
+            .split(';')
+            .filter_map(|cookie| cookie.trim().split_once('='))
+            .find(|(n, _)| *n == name)
+            .map(|(_, value)| value)
+    }
+
+    /// A field of a `application/x-www-form-urlencoded` body.
+    pub fn form(&self, name: &str) -> Option<String> {
+        self.body
+            .split('&')
+            .filter_map(|field| field.split_once('='))
+            .find(|(n, _)| *n == name)
+            .map(|(_, value)| url_decode(value))
+    }
+}
+
+fn url_decode(s: &str) -> String {
+    let mut bytes = vec![];
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        rest = tail;
+        match b {
+            b'+' => bytes.push(b' '),
+            b'%' if rest.len() >= 2 => {
+                match std::str::from_utf8(&rest[..2])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    Some(decoded) => {
+                        bytes.push(decoded);
+                        rest = &rest[2..];
+                    }
+                    None => bytes.push(b),
+                }
+            }
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    fn ok(body: impl Into<String>) -> Self {
+        Response {
+            status: 200,
+            body: body.into(),
+        }
+    }
+
+    fn not_found() -> Self {
+        Response {
+            status: 404,
+            body: "404 Not Found".to_string(),
+        }
+    }
+
+    /// Wrap a message the way the real site does on its answer page.
+    fn article(message: &str) -> Self {
+        Response::ok(format!(
+            "<!DOCTYPE html>\n<html lang=\"en-us\">\n<body>\n<main>\n<article><p>{message}</p></article>\n</main>\n</body>\n</html>\n"
+        ))
+    }
+
+    pub fn write_to(&self, writer: &mut impl io::Write) -> io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            _ => "Unknown",
+        };
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason,
+            self.body.len(),
+            self.body
+        )?;
+        writer.flush()
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// `(year, day, part)` already answered correctly
+    solved: HashSet<(u32, u32, u8)>,
+    /// wrong answers lock out further submissions until then
+    locked_until: Option<Instant>,
+}
+
+#[derive(Debug)]
+pub struct MockServer {
+    fixtures: PathBuf,
+    cooldown: Duration,
+    state: Mutex<State>,
+}
+
+impl MockServer {
+    /// Serve the fixtures in `fixtures`, refusing submissions for `cooldown`
+    /// after each wrong answer.
+    pub fn new(fixtures: impl Into<PathBuf>, cooldown: Duration) -> Self {
+        MockServer {
+            fixtures: fixtures.into(),
+            cooldown,
+            state: Mutex::default(),
+        }
+    }
+
+    fn fixture(&self, year: u32, day: u32, name: &str) -> Option<String> {
+        fs::read_to_string(
+            self.fixtures
+                .join(year.to_string())
+                .join(format!("{day:02}"))
+                .join(name),
+        )
+        .ok()
+    }
+
+    pub fn handle(&self, request: &Request) -> Response {
+        let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+        let (year, day, rest) = match segments.as_slice() {
+            [year, "day", day, rest @ ..] => match (year.parse(), day.parse()) {
+                (Ok(year), Ok(day)) => (year, day, rest),
+                _ => return Response::not_found(),
+            },
+            _ => return Response::not_found(),
+        };
+
+        match (request.method.as_str(), rest) {
+            ("GET", []) => self
+                .fixture(year, day, "puzzle.html")
+                .map_or_else(Response::not_found, Response::ok),
+            ("GET", ["input"]) => {
+                if request.cookie("session").is_none() {
+                    return Response {
+                        status: 400,
+                        body: "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n"
+                            .to_string(),
+                    };
+                }
+                self.fixture(year, day, "input.txt")
+                    .map_or_else(Response::not_found, Response::ok)
+            }
+            ("POST", ["answer"]) => self.submit(year, day, request),
+            _ => Response::not_found(),
+        }
+    }
+
+    fn submit(&self, year: u32, day: u32, request: &Request) -> Response {
+        if request.cookie("session").is_none() {
+            return Response {
+                status: 400,
+                body: "Please log in to submit answers.".to_string(),
+            };
+        }
+        let (Some(level), Some(answer)) = (request.form("level"), request.form("answer")) else {
+            return Response {
+                status: 400,
+                body: "Missing `level` or `answer`.".to_string(),
+            };
+        };
+        let Some(part) = level.parse::<u8>().ok().filter(|p| (1..=2).contains(p)) else {
+            return Response::not_found();
+        };
+        let Some(expected) = self.fixture(year, day, &format!("answer{part}.txt")) else {
+            return Response::not_found();
+        };
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        if let Some(left) = state
+            .locked_until
+            .and_then(|until| until.checked_duration_since(now))
+        {
+            return Response::article(&format!(
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have {}s left to wait.",
+                left.as_secs_f64().ceil() as u64
+            ));
+        }
+        if state.solved.contains(&(year, day, part)) {
+            return Response::article(
+                "You don't seem to be solving the right level.  Did you already complete it?",
+            );
+        }
+
+        let (answer, expected) = (answer.trim(), expected.trim());
+        if answer == expected {
+            state.solved.insert((year, day, part));
+            return Response::article(
+                "That's the right answer!  You are one gold star closer to restoring snow operations.",
+            );
+        }
+
+        state.locked_until = Some(now + self.cooldown);
+        let hint = match (answer.parse::<i128>(), expected.parse::<i128>()) {
+            (Ok(answer), Ok(expected)) if answer > expected => "  your answer is too high.",
+            (Ok(answer), Ok(expected)) if answer < expected => "  your answer is too low.",
+            _ => "",
+        };
+        Response::article(&format!(
+            "That's not the right answer;{hint}  Please wait {}s before trying again.",
+            self.cooldown.as_secs()
+        ))
+    }
+
+    fn handle_stream(&self, stream: TcpStream) -> io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let response = match Request::read(&mut reader) {
+            Ok(request) => self.handle(&request),
+            Err(e) => Response {
+                status: 400,
+                body: e.to_string(),
+            },
+        };
+        let mut stream = stream;
+        response.write_to(&mut stream)
+    }
+
+    /// Answer connections on `listener` until it fails.
+    pub fn serve(self: Arc<Self>, listener: TcpListener) -> io::Result<()> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let server = Arc::clone(&self);
+            thread::spawn(move || {
+                if let Err(e) = server.handle_stream(stream) {
+                    eprintln!("mock-aoc: {e}");
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Serve on a free local port in the background, returning its address.
+    pub fn spawn(self) -> io::Result<SocketAddr> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let server = Arc::new(self);
+        thread::spawn(move || server.serve(listener));
+        Ok(addr)
+    }
+}
+
+/// The fixtures bundled with this crate.
+pub fn bundled_fixtures() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read as _, Write as _};
+
+    fn request(method: &str, path: &str, body: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            headers: vec![("cookie".to_string(), "session=abc".to_string())],
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_read_request() -> anyhow::Result<()> {
+        let raw = "POST /2023/day/1/answer HTTP/1.1\r\nHost: localhost\r\nCookie: a=1; session=abc\r\nContent-Length: 20\r\n\r\nlevel=1&answer=1+2%3";
+        let request = Request::read(&mut raw.as_bytes())?;
+        assert_eq!("POST", request.method);
+        assert_eq!("/2023/day/1/answer", request.path);
+        assert_eq!(Some("abc"), request.cookie("session"));
+        assert_eq!(Some("1".to_string()), request.form("level"));
+        assert_eq!(Some("1 2%3".to_string()), request.form("answer"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fixtures() {
+        let server = MockServer::new(bundled_fixtures(), Duration::ZERO);
+        let input = server.handle(&request("GET", "/2023/day/1/input", ""));
+        assert_eq!(200, input.status);
+        assert!(input.body.starts_with("1abc2"));
+
+        assert_eq!(
+            200,
+            server.handle(&request("GET", "/2023/day/1", "")).status
+        );
+        assert_eq!(
+            404,
+            server
+                .handle(&request("GET", "/2023/day/25/input", ""))
+                .status
+        );
+
+        let mut anonymous = request("GET", "/2023/day/1/input", "");
+        anonymous.headers.clear();
+        assert!(server.handle(&anonymous).body.contains("Please log in"));
+    }
+
+    #[test]
+    fn test_submit() {
+        let server = MockServer::new(bundled_fixtures(), Duration::ZERO);
+        let submit = |answer: &str| {
+            server
+                .handle(&request(
+                    "POST",
+                    "/2023/day/1/answer",
+                    &format!("level=1&answer={answer}"),
+                ))
+                .body
+        };
+        assert!(submit("200").contains("too high"));
+        assert!(submit("100").contains("too low"));
+        assert!(submit("142").contains("That's the right answer"));
+        assert!(submit("142").contains("Did you already complete it"));
+    }
+
+    #[test]
+    fn test_cooldown() {
+        let server = MockServer::new(bundled_fixtures(), Duration::from_secs(60));
+        let submit = |answer: &str| {
+            server
+                .handle(&request(
+                    "POST",
+                    "/2023/day/1/answer",
+                    &format!("level=1&answer={answer}"),
+                ))
+                .body
+        };
+        assert!(submit("1").contains("not the right answer"));
+        assert!(submit("142").contains("You have 60s left to wait"));
+    }
+
+    #[test]
+    fn test_spawn() -> anyhow::Result<()> {
+        let addr = MockServer::new(bundled_fixtures(), Duration::ZERO).spawn()?;
+        let mut stream = TcpStream::connect(addr)?;
+        stream.write_all(b"GET /2023/day/1/input HTTP/1.1\r\nCookie: session=abc\r\n\r\n")?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.ends_with("treb7uchet\n"), "{response}");
+
+        Ok(())
+    }
+}
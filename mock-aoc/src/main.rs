@@ -0,0 +1,39 @@
+use anyhow::{Context as _, Result};
+use clap::Parser;
+use mock_aoc::{bundled_fixtures, MockServer};
+use std::{net::TcpListener, path::PathBuf, sync::Arc, time::Duration};
+
+/// serve fixture inputs, puzzle pages and answer submissions in place of
+/// adventofcode.com. point the fetcher at it with
+/// `AOC_BASE_URL=http://127.0.0.1:<port>`
+#[derive(Parser, Debug)]
+#[clap(version)]
+struct Args {
+    /// directory of `{year}/{day:02}/` fixtures, the bundled ones by default
+    #[clap(long)]
+    fixtures: Option<PathBuf>,
+    /// the port to listen on
+    #[clap(short, long, default_value_t = 3000)]
+    port: u16,
+    /// seconds to refuse submissions for after a wrong answer
+    #[clap(long, default_value_t = 60)]
+    cooldown: u64,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let fixtures = args
+        .fixtures
+        .unwrap_or_else(|| bundled_fixtures().to_path_buf());
+    let listener = TcpListener::bind(("127.0.0.1", args.port))
+        .with_context(|| format!("binding port {}", args.port))?;
+    println!(
+        "serving {} on http://{}",
+        fixtures.display(),
+        listener.local_addr()?
+    );
+
+    let server = MockServer::new(fixtures, Duration::from_secs(args.cooldown));
+    Arc::new(server).serve(listener)?;
+    Ok(())
+}
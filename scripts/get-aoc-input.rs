@@ -7,16 +7,26 @@
 //! edition = "2021"
 //!
 //! [dependencies]
-//! clap = { version = "4.2", features = ["derive"] }
+//! clap = { version = "4.2", features = ["derive", "env"] }
+//! common = { path = "../common" }
 //! nom = "7.1.3"
 //! reqwest = { version = "0.11.22", features=["blocking"] }
 //! dotenv = "0.15.0"
 //! ```
 
 use clap::{error::ErrorKind, CommandFactory, Parser};
+use common::{
+    examples::Example,
+    puzzle::{articles, examples, html_to_markdown},
+};
 use nom::{bytes::complete::tag, character::complete, sequence::preceded, IResult};
 use reqwest::{blocking::Client, header::COOKIE};
-use std::{fs::File, io::Write, path::PathBuf};
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 #[derive(Parser, Debug)]
 #[clap(version)]
@@ -34,16 +44,103 @@ struct Args {
     /// doing any shenanigans
     #[clap(long)]
     current_working_directory: PathBuf,
+    /// where downloaded inputs are kept, defaults
+    /// to `.aoc-cache` in the working directory
+    #[clap(long, env = "AOC_CACHE_DIR")]
+    cache_dir: Option<PathBuf>,
+    /// only use the cache, never the network
+    #[clap(long, conflicts_with = "force")]
+    offline: bool,
+    /// download again even when the input is cached
+    #[clap(long)]
+    force: bool,
+    /// the site to download from, e.g. a local
+    /// `mock-aoc` server
+    #[clap(
+        long,
+        env = "AOC_BASE_URL",
+        default_value = "https://adventofcode.com"
+    )]
+    base_url: String,
 }
 
 fn parse_day(input: &str) -> IResult<&str, u32> {
     preceded(tag("day-"), complete::u32)(input)
 }
 
-fn main() -> Result<(), reqwest::Error> {
+/// Advent of Code answers most bad requests with a 200 and a short message
+/// instead of the input, so look for those before trusting a body.
+fn validate(body: &str) -> Result<(), String> {
+    const ERROR_PAGES: &[&str] = &[
+        "Please log in",
+        "Please don't repeatedly request this endpoint before it unlocks",
+        "404 Not Found",
+        "500 Internal Server Error",
+    ];
+    if body.trim().is_empty() {
+        return Err("the input is empty".to_string());
+    }
+    if let Some(message) = ERROR_PAGES.iter().find(|m| body.contains(*m)) {
+        return Err(format!("got an error page instead of the input: `{message}`"));
+    }
+    if body.trim_start().starts_with('<') {
+        return Err("got an html page instead of the input".to_string());
+    }
+    Ok(())
+}
+
+fn fetch(url: &str) -> Result<String, Box<dyn Error>> {
+    let session = std::env::var("SESSION").map_err(|_| "should have a session token set")?;
+    println!("sending to `{}`", url);
+
+    let client = Client::builder()
+        .user_agent("get-aoc-input (personal advent of code workspace, rust)")
+        .build()?;
+    let response = client
+        .get(url)
+        .header(COOKIE, format!("session={session}"))
+        .send()?;
+    let status = response.status();
+    let body = response.text()?;
+    if !status.is_success() {
+        return Err(format!("request failed with {status}: {}", body.trim()).into());
+    }
+    Ok(body)
+}
+
+/// Write `examples/partN.txt` and `examples/partN.answer` for each part's
+/// example. Existing files are kept unless `force`, as they may have been
+/// fixed up by hand.
+fn write_examples(
+    day_dir: &Path,
+    examples: Vec<Option<Example>>,
+    force: bool,
+) -> std::io::Result<()> {
+    let dir = day_dir.join("examples");
+    for (i, example) in examples.into_iter().enumerate() {
+        let part = i + 1;
+        let Some(Example { input, answer }) = example else {
+            println!("no example found for part {part}");
+            continue;
+        };
+
+        let input_path = dir.join(format!("part{part}.txt"));
+        let answer_path = dir.join(format!("part{part}.answer"));
+        if !force && (input_path.exists() || answer_path.exists()) {
+            println!("keeping existing example for part {part}");
+            continue;
+        }
+        fs::create_dir_all(&dir)?;
+        fs::write(&input_path, input)?;
+        fs::write(&answer_path, format!("{answer}\n"))?;
+        println!("wrote {} (answer `{answer}`)", input_path.display());
+    }
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
     dotenv::dotenv().ok();
 
-    let session = std::env::var("SESSION").expect("should have a session token set");
     let args = Args::parse();
     let Ok((_, day)) = parse_day(&args.day) else {
         let mut cmd = Args::command();
@@ -55,26 +152,91 @@ fn main() -> Result<(), reqwest::Error> {
     };
 
     let year = args.year;
-    let url = format!("https://adventofcode.com/{year}/day/{day}/input");
-    println!("sending to `{}`", url);
+    let base_url = args.base_url.trim_end_matches('/');
+    let cache_dir = args
+        .cache_dir
+        .unwrap_or_else(|| args.current_working_directory.join(".aoc-cache"));
+    let cache_path = cache_dir
+        .join(year.to_string())
+        .join(format!("{day:02}"))
+        .join("input.txt");
 
-    let client = Client::new();
-    let input_data = client
-        .get(url)
-        .header(COOKIE, format!("session={session}"))
-        .send()?
-        .text()?;
+    let cached = match fs::read_to_string(&cache_path) {
+        Ok(body) if !args.force => match validate(&body) {
+            Ok(()) => Some(body),
+            Err(e) => {
+                println!("ignoring cached {}: {e}", cache_path.display());
+                None
+            }
+        },
+        _ => None,
+    };
+
+    let input_data = match cached {
+        Some(body) => {
+            println!("using cached {}", cache_path.display());
+            body
+        }
+        None if args.offline => {
+            return Err(format!(
+                "--offline was given but there is no usable input at {}",
+                cache_path.display()
+            )
+            .into())
+        }
+        None => {
+            let body = fetch(&format!("{base_url}/{year}/day/{day}/input"))?;
+            validate(&body)?;
+            if let Some(dir) = cache_path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&cache_path, &body)?;
+            println!("cached {}", cache_path.display());
+            body
+        }
+    };
+
+    let day_dir = args.current_working_directory.join(&args.day);
+    let file_path = day_dir.join("input.txt");
+    let mut file = File::create(&file_path).expect("should be able to create a file");
+    file.write_all(input_data.as_bytes())
+        .expect("should be able to write to input file");
+    println!("wrote {}", file_path.display());
 
+    // per-part files are overrides for hand-crafted inputs, so only clear out
+    // the copies of the real input that older versions of this script wrote
     for filename in ["input1.txt", "input2.txt"] {
-        let file_path = args
-            .current_working_directory
-            .join(&args.day)
-            .join(filename);
-        let mut file = File::create(&file_path).expect("should be able to create a file");
-
-        file.write_all(input_data.as_bytes())
-            .expect("should be able to write to input file");
-        println!("wrote {}", file_path.display());
+        let file_path = day_dir.join(filename);
+        if fs::read_to_string(&file_path).is_ok_and(|existing| existing == input_data) {
+            fs::remove_file(&file_path).expect("should be able to remove duplicate input");
+            println!("removed duplicate {}", file_path.display());
+        }
+    }
+
+    // the description and examples are a convenience, so don't fail the
+    // input over them
+    if args.offline {
+        return Ok(());
+    }
+    let page_url = format!("{base_url}/{year}/day/{day}");
+    match fetch(&page_url) {
+        Ok(page) if !articles(&page).is_empty() => {
+            let markdown: Vec<String> = articles(&page)
+                .iter()
+                .map(|article| html_to_markdown(article, base_url, &page_url))
+                .collect();
+            let readme_path = day_dir.join("README.md");
+            fs::write(
+                &readme_path,
+                format!("{}\n[Puzzle page]({page_url})\n", markdown.join("\n")),
+            )?;
+            println!("wrote {}", readme_path.display());
+            if let Err(e) = write_examples(&day_dir, examples(&page), args.force) {
+                println!("couldn't write the examples: {e}");
+            }
+        }
+        Ok(_) => println!("no puzzle description found at `{page_url}`"),
+        Err(e) => println!("couldn't download the puzzle description or examples: {e}"),
     }
 
     Ok(())
@@ -1,16 +1,30 @@
-pub fn run(_input: &str) -> anyhow::Result<String> {
-    todo!("part 1 unimplemented")
+use common::{Part, Solution};
+
+pub struct Part1;
+
+impl Solution for Part1 {
+    type Input<'a> = &'a str;
+
+    const DAY: u8 = {{day}};
+    const PART: Part = Part::One;
+    const TITLE: &'static str = "";
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(input)
+    }
+
+    fn solve(_input: &Self::Input<'_>) -> anyhow::Result<String> {
+        anyhow::bail!("part 1 is not implemented yet")
+    }
+}
+
+pub fn run(input: &str) -> anyhow::Result<String> {
+    Part1::solve(&Part1::parse(input)?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_run() -> anyhow::Result<()> {
-        let input = "";
-        assert_eq!("", run(input)?);
-
-        Ok(())
-    }
+    common::example_test!(run, 1);
 }